@@ -0,0 +1,477 @@
+//! Sharing boards as fumen strings, the `v115@...` format of the fumen editor and viewer.
+//!
+//! A fumen is a list of pages, each holding a 10 wide field, an optional piece placed on it and
+//! an optional comment. Positions are written one per page, with their pieces in a quiz comment,
+//! `#Q=[hold](current)queue`, which fumen viewers also understand. When reading a fumen without
+//! one, the pieces placed on its pages from the first one on are taken as the queue instead.
+
+use std::{error::Error, fmt};
+
+use crate::playfield::{Grid, Mino, Position, tetromino::Tetromino};
+
+const PREFIX: &str = "115@";
+/// The versions of the format sharing the v115 encoding: view, mobile and edit.
+const VERSIONS: [char; 3] = ['v', 'm', 'd'];
+const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const WIDTH: usize = 10;
+/// Rows above the floor, not counting the garbage row under it.
+const HEIGHT: usize = 23;
+/// Every cell of a page, from the top row down and then the garbage row.
+const BLOCKS: usize = WIDTH * (HEIGHT + 1);
+/// A run covering the whole field without changing it.
+const UNCHANGED: u32 = 8 * BLOCKS as u32 + BLOCKS as u32 - 1;
+
+/// The characters comments are made of, in the order their codes count them.
+const COMMENT_CHARS: u32 = 96;
+const QUIZ_PREFIX: &str = "#Q=";
+
+/// The minos of a page, as fumen numbers them, indexed as `field[y][x]` from the floor up.
+type Field = [[u8; WIDTH]; HEIGHT];
+
+/// Writes `positions` as a fumen with one page each. Their boards must be 10 wide and at most
+/// 23 rows high.
+pub fn encode(positions: &[Position]) -> Result<String, FumenError> {
+  let mut values = Vec::new();
+  let mut prev_field = [[0; WIDTH]; HEIGHT];
+  let mut prev_comment = String::new();
+  // The count of pages after the last changed field that repeat it, while it can still grow
+  let mut repeat_count: Option<usize> = None;
+
+  for (page, position) in positions.iter().enumerate() {
+    let field = field(&position.rows)?;
+    let runs = field_runs(&prev_field, &field);
+    if runs != [UNCHANGED] {
+      for run in runs {
+        push(&mut values, run, 2);
+      }
+      repeat_count = None;
+    } else if let Some(count) = repeat_count.filter(|&count| values[count] < 63) {
+      values[count] += 1;
+    } else {
+      push(&mut values, UNCHANGED, 2);
+      values.push(0);
+      repeat_count = Some(values.len() - 1);
+    }
+
+    let comment = quiz(position);
+    let has_comment = comment != prev_comment;
+    // No piece is placed, the page locks, and only the first page asks for guideline colors
+    let flags = u32::from(has_comment) * 8 + u32::from(page == 0) * 4;
+    push(&mut values, flags * BLOCKS as u32 * 4 * 8, 3);
+    if has_comment {
+      push_comment(&mut values, &comment);
+    }
+
+    prev_field = field;
+    prev_comment = comment;
+  }
+
+  let data: String = values
+    .into_iter()
+    .map(|value| TABLE[value as usize] as char)
+    .collect();
+  // The editor breaks long fumens up with question marks, after 42 characters then every 47
+  let mut fumen = format!("v{PREFIX}");
+  for (i, c) in data.chars().enumerate() {
+    if i >= 42 && (i - 42).is_multiple_of(47) {
+      fumen.push('?');
+    }
+    fumen.push(c);
+  }
+  Ok(fumen)
+}
+
+/// Reads the first page of a fumen, which may be part of a link to a fumen viewer.
+pub fn decode(text: &str) -> Result<Position, FumenError> {
+  let start = text.find(PREFIX).ok_or(FumenError::Version)?;
+  if !text[..start].ends_with(VERSIONS) {
+    return Err(FumenError::Version);
+  }
+  let values = text[start + PREFIX.len()..]
+    .chars()
+    .take_while(|c| !c.is_whitespace())
+    .filter(|&c| c != '?')
+    .map(|c| TABLE.iter().position(|&d| d as char == c).map(|v| v as u32))
+    .collect::<Option<Vec<_>>>()
+    .ok_or(FumenError::Data)?;
+  let mut reader = Reader { values, next: 0 };
+
+  let mut field = [[0; WIDTH]; HEIGHT];
+  let mut comment = String::new();
+  let mut placed = Vec::new();
+  let mut repeat_count = 0;
+  let mut page = 0;
+  while !reader.is_empty() {
+    if repeat_count > 0 {
+      repeat_count -= 1;
+    } else {
+      let mut block = 0;
+      while block < BLOCKS {
+        let run = reader.read(2)?;
+        if run == UNCHANGED {
+          repeat_count = reader.read(1)?;
+        }
+        let (diff, count) = (run / BLOCKS as u32, run as usize % BLOCKS + 1);
+        if block + count > BLOCKS {
+          return Err(FumenError::Data);
+        }
+        // Later pages only matter for the pieces they place
+        if page == 0 {
+          let mino = diff
+            .checked_sub(8)
+            .filter(|&mino| mino <= 8)
+            .ok_or(FumenError::Data)?;
+          for block in block..block + count {
+            let (x, row) = (block % WIDTH, block / WIDTH);
+            if row < HEIGHT {
+              field[HEIGHT - 1 - row][x] = mino as u8;
+            }
+          }
+        }
+        block += count;
+      }
+    }
+
+    let action = reader.read(3)?;
+    if let Some(Some(tetromino)) = piece(action % 8) {
+      placed.push(tetromino);
+    }
+    let has_comment = action / (8 * 4 * BLOCKS as u32 * 2 * 2 * 2) % 2 == 1;
+    if has_comment {
+      let text = read_comment(&mut reader)?;
+      if page == 0 {
+        comment = text;
+      }
+    }
+    page += 1;
+  }
+  if page == 0 {
+    return Err(FumenError::Data);
+  }
+
+  let rows: Grid = field
+    .iter()
+    .map(|row| row.iter().map(|&mino| mino_of(mino)).collect())
+    .collect();
+  let height = rows
+    .iter()
+    .rposition(|row| row.iter().any(Option::is_some))
+    .map_or(0, |top| top + 1);
+  let (pieces, hold) = match comment.strip_prefix(QUIZ_PREFIX) {
+    Some(quiz) => parse_quiz(quiz)?,
+    None => (placed, None),
+  };
+  Ok(Position {
+    rows: rows[..height].to_vec(),
+    pieces,
+    hold,
+  })
+}
+
+/// Why a fumen could not be read or written.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FumenError {
+  Version,
+  Data,
+  Width(usize),
+  TooTall,
+  UnknownPiece(char),
+}
+
+impl fmt::Display for FumenError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Version => write!(f, "not a v115 fumen"),
+      Self::Data => write!(f, "the fumen data is invalid or cut short"),
+      Self::Width(width) => write!(f, "fumen boards are 10 cells wide, not {width}"),
+      Self::TooTall => write!(f, "fumen boards are at most 23 rows high"),
+      Self::UnknownPiece(piece) => write!(f, "unknown piece '{piece}' in the quiz"),
+    }
+  }
+}
+
+impl Error for FumenError {}
+
+/// Reads the values of a fumen in groups, each written with its least significant digit first.
+struct Reader {
+  values: Vec<u32>,
+  next: usize,
+}
+
+impl Reader {
+  fn is_empty(&self) -> bool {
+    self.next >= self.values.len()
+  }
+
+  fn read(&mut self, digits: usize) -> Result<u32, FumenError> {
+    let digits = self
+      .values
+      .get(self.next..self.next + digits)
+      .ok_or(FumenError::Data)?;
+    self.next += digits.len();
+    Ok(
+      digits
+        .iter()
+        .rev()
+        .fold(0, |value, &digit| value * 64 + digit),
+    )
+  }
+}
+
+fn push(values: &mut Vec<u32>, mut value: u32, digits: usize) {
+  for _ in 0..digits {
+    values.push(value % 64);
+    value /= 64;
+  }
+}
+
+/// The fumen number of a mino.
+fn number(mino: Option<Mino>) -> u8 {
+  match mino {
+    None => 0,
+    Some(Mino::Piece(tetromino)) => match tetromino {
+      Tetromino::I => 1,
+      Tetromino::L => 2,
+      Tetromino::O => 3,
+      Tetromino::Z => 4,
+      Tetromino::T => 5,
+      Tetromino::J => 6,
+      Tetromino::S => 7,
+    },
+    Some(Mino::Garbage) => 8,
+  }
+}
+
+/// The piece numbered `number`, if it is one, which is `Some(None)` for an empty cell.
+fn piece(number: u32) -> Option<Option<Tetromino>> {
+  match number {
+    0 => Some(None),
+    1 => Some(Some(Tetromino::I)),
+    2 => Some(Some(Tetromino::L)),
+    3 => Some(Some(Tetromino::O)),
+    4 => Some(Some(Tetromino::Z)),
+    5 => Some(Some(Tetromino::T)),
+    6 => Some(Some(Tetromino::J)),
+    7 => Some(Some(Tetromino::S)),
+    _ => None,
+  }
+}
+
+fn mino_of(number: u8) -> Option<Mino> {
+  match piece(number.into()) {
+    Some(tetromino) => tetromino.map(Mino::Piece),
+    None => Some(Mino::Garbage),
+  }
+}
+
+fn field(rows: &Grid) -> Result<Field, FumenError> {
+  let mut field = [[0; WIDTH]; HEIGHT];
+  for (y, row) in rows.iter().enumerate() {
+    if row.len() != WIDTH {
+      return Err(FumenError::Width(row.len()));
+    }
+    if y >= HEIGHT {
+      if row.iter().any(Option::is_some) {
+        return Err(FumenError::TooTall);
+      }
+      continue;
+    }
+    for (x, &mino) in row.iter().enumerate() {
+      field[y][x] = number(mino);
+    }
+  }
+  Ok(field)
+}
+
+/// The changes from `prev` to `current` as runs of cells changing by the same amount, with the
+/// amount and the length of each packed into one value.
+fn field_runs(prev: &Field, current: &Field) -> Vec<u32> {
+  // The garbage row below the floor is always empty
+  let diffs = (0..BLOCKS).map(|block| {
+    let (x, row) = (block % WIDTH, block / WIDTH);
+    if row < HEIGHT {
+      let y = HEIGHT - 1 - row;
+      8 + u32::from(current[y][x]) - u32::from(prev[y][x])
+    } else {
+      8
+    }
+  });
+
+  let mut runs = Vec::new();
+  let mut run: Option<(u32, u32)> = None;
+  for diff in diffs {
+    run = match run {
+      Some((run_diff, count)) if run_diff == diff => Some((diff, count + 1)),
+      Some((run_diff, count)) => {
+        runs.push(run_diff * BLOCKS as u32 + count - 1);
+        Some((diff, 1))
+      }
+      None => Some((diff, 1)),
+    };
+  }
+  if let Some((diff, count)) = run {
+    runs.push(diff * BLOCKS as u32 + count - 1);
+  }
+  runs
+}
+
+/// The quiz comment holding the pieces of `position`, or nothing without any.
+fn quiz(position: &Position) -> String {
+  let Some((current, queue)) = position.pieces.split_first() else {
+    return String::new();
+  };
+  let hold = position.hold.map_or("", Tetromino::name);
+  let queue: String = queue.iter().map(|tetromino| tetromino.name()).collect();
+  format!("{QUIZ_PREFIX}[{hold}]({}){queue}", current.name())
+}
+
+/// The pieces and hold piece of a quiz, from `[hold](current)queue`.
+fn parse_quiz(quiz: &str) -> Result<(Vec<Tetromino>, Option<Tetromino>), FumenError> {
+  let mut hold = None;
+  let mut pieces = Vec::new();
+  let mut in_hold = false;
+  for c in quiz.chars() {
+    match c {
+      '[' => in_hold = true,
+      ']' => in_hold = false,
+      '(' | ')' => {}
+      // Anything after the queue is a note for the reader
+      ';' | ' ' => break,
+      _ => {
+        let tetromino = Tetromino::from_char(c).ok_or(FumenError::UnknownPiece(c))?;
+        if in_hold {
+          hold = Some(tetromino);
+        } else {
+          pieces.push(tetromino);
+        }
+      }
+    }
+  }
+  Ok((pieces, hold))
+}
+
+/// Writes a comment as its length, then four characters at a time. Comments are escaped first,
+/// as JavaScript's `escape` does.
+fn push_comment(values: &mut Vec<u32>, comment: &str) {
+  let mut escaped = String::new();
+  for c in comment.chars() {
+    if c.is_ascii_alphanumeric() || "@*_+-./".contains(c) {
+      escaped.push(c);
+    } else if (c as u32) < 256 {
+      escaped.push_str(&format!("%{:02X}", c as u32));
+    } else {
+      escaped.push_str(&format!("%u{:04X}", c as u32));
+    }
+  }
+
+  push(values, escaped.len() as u32, 2);
+  for chunk in escaped.as_bytes().chunks(4) {
+    let value = chunk
+      .iter()
+      .rev()
+      .fold(0, |value, &c| value * COMMENT_CHARS + u32::from(c - b' '));
+    push(values, value, 5);
+  }
+}
+
+fn read_comment(reader: &mut Reader) -> Result<String, FumenError> {
+  let length = reader.read(2)? as usize;
+  let mut escaped = Vec::with_capacity(length + 3);
+  for _ in 0..length.div_ceil(4) {
+    let mut value = reader.read(5)?;
+    for _ in 0..4 {
+      escaped.push(char::from(b' ' + (value % COMMENT_CHARS) as u8));
+      value /= COMMENT_CHARS;
+    }
+  }
+  escaped.truncate(length);
+
+  let mut comment = String::new();
+  let mut chars = escaped.into_iter();
+  while let Some(c) = chars.next() {
+    if c != '%' {
+      comment.push(c);
+      continue;
+    }
+    let rest = chars.as_slice();
+    let (digits, skip) = match rest.first() {
+      Some('u') => (rest.get(1..5), 5),
+      _ => (rest.get(..2), 2),
+    };
+    let code = digits
+      .map(|digits| digits.iter().collect::<String>())
+      .and_then(|digits| u32::from_str_radix(&digits, 16).ok())
+      .and_then(char::from_u32);
+    match code {
+      Some(code) => {
+        comment.push(code);
+        chars.nth(skip - 1);
+      }
+      None => comment.push(c),
+    }
+  }
+  Ok(comment)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A row from its cells left to right, `#` being garbage and letters the pieces they name.
+  fn row(cells: &str) -> Vec<Option<Mino>> {
+    cells
+      .chars()
+      .map(|c| match c {
+        '.' => None,
+        '#' => Some(Mino::Garbage),
+        _ => Tetromino::ALL
+          .into_iter()
+          .find(|tetromino| tetromino.name().starts_with(c))
+          .map(Mino::Piece),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn positions_survive_a_round_trip() {
+    let position = Position {
+      rows: vec![
+        row("##.#######"),
+        row("IIII.ZZ###"),
+        row("LLL..OOZZ."),
+        row("L.SSTTTOOJ"),
+        row("..SS.T..JJ"),
+      ],
+      pieces: vec![Tetromino::T, Tetromino::I, Tetromino::O, Tetromino::S],
+      hold: Some(Tetromino::L),
+    };
+    let fumen = encode(std::slice::from_ref(&position)).unwrap();
+    assert_eq!(decode(&fumen), Ok(position));
+  }
+
+  #[test]
+  fn fumens_match_the_editor() {
+    // Written by the fumen editor: an empty field, then one with the bottom row filled
+    let empty = Position {
+      rows: Vec::new(),
+      pieces: Vec::new(),
+      hold: None,
+    };
+    let filled = Position {
+      rows: vec![row("##########")],
+      ..empty.clone()
+    };
+    for (fumen, position) in [("v115@vhAAgH", empty), ("v115@bhJ8JeAgH", filled)] {
+      assert_eq!(
+        encode(std::slice::from_ref(&position)).as_deref(),
+        Ok(fumen)
+      );
+      assert_eq!(decode(fumen), Ok(position));
+    }
+    assert_eq!(
+      decode("https://fumen.zui.jp/?v115@bhJ8JeAgH").map(|position| position.rows.len()),
+      Ok(1)
+    );
+  }
+}
@@ -0,0 +1,345 @@
+//! Scoring on top of a [`Playfield`].
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{
+  HandlingSettings,
+  garbage::{self, GARBAGE_CAP, GarbageQueue},
+  input::Input,
+  level,
+  line_clear_message::{LineClearMessage, MessageType, SpinType},
+  playfield::{Mino, Playfield, UpdateInfo, finesse::Finesse, tetromino::Tetromino},
+  rules::{Rules, Style},
+  stats::Stats,
+};
+
+/// A playfield together with its score, combo and back-to-back counters, the garbage it
+/// exchanges with an opponent and its [`Stats`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Game {
+  playfield: Playfield,
+  combo: u32,
+  /// The lines cleared since the current combo started.
+  combo_lines: u32,
+  score: u64,
+  b2b: u32,
+  message: LineClearMessage,
+  lines: u32,
+  level: u32,
+  incoming_garbage: GarbageQueue,
+  outgoing_garbage: u32,
+  #[serde(default)]
+  stats: Stats,
+  /// The last placement that took more keys than needed, with how long it stays on screen.
+  #[serde(default)]
+  finesse_fault: Option<(Finesse, Duration)>,
+}
+
+impl Game {
+  pub fn new(seed: u64, rules: Rules) -> Self {
+    Game {
+      playfield: Playfield::new(seed, rules),
+      combo: 0,
+      combo_lines: 0,
+      score: 0,
+      b2b: 0,
+      message: LineClearMessage::empty(),
+      lines: 0,
+      level: level::first_level(rules.style),
+      incoming_garbage: GarbageQueue::new(
+        seed,
+        rules.board.width as usize,
+        rules.garbage_messiness,
+      ),
+      outgoing_garbage: 0,
+      stats: Stats::new(),
+      finesse_fault: None,
+    }
+  }
+
+  /// Advances the game by `dt`, returning what happened if a piece locked on this frame.
+  pub fn update(
+    &mut self,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> Option<UpdateInfo> {
+    self.message.remaining_time = self.message.remaining_time.saturating_sub(dt);
+    if let Some((_, time)) = &mut self.finesse_fault {
+      *time = time.saturating_sub(dt);
+    }
+    self.finesse_fault.take_if(|(_, time)| time.is_zero());
+    if !self.has_lost() {
+      self.stats.add_frame(dt, input);
+    }
+
+    let level = self.level();
+    let settings = match self.playfield.rules().style {
+      Style::Guideline => HandlingSettings {
+        gravity: level::gravity(level),
+        lock_delay: settings.lock_delay.min(level::lock_delay(level)),
+        ..*settings
+      },
+      // Pieces lock on the first gravity step they cannot take
+      Style::Classic => HandlingSettings {
+        gravity: level::classic_gravity(level),
+        lock_delay: level::classic_gravity(level),
+        ..*settings
+      },
+      Style::Master => HandlingSettings {
+        gravity: level::master_gravity(level),
+        lock_delay: level::MASTER_LOCK_DELAY,
+        entry_delay: level::MASTER_ENTRY_DELAY,
+        line_clear_delay: level::MASTER_LINE_CLEAR_DELAY,
+        ..*settings
+      },
+    };
+
+    let update_info = self.playfield.update(input, &settings, dt)?;
+    self.update_score(&update_info);
+    let attack = self.update_garbage(&update_info);
+    self
+      .stats
+      .add_lock(attack, update_info.finesse, &self.message);
+    if let Some(finesse) = update_info.finesse.filter(|finesse| finesse.is_fault()) {
+      self.finesse_fault = Some((finesse, Finesse::FAULT_DURATION));
+    }
+    Some(update_info)
+  }
+
+  /// Starts over on an empty board with a queue seeded with `seed`, keeping the same rules.
+  pub fn reset(&mut self, seed: u64) {
+    let rules = *self.playfield.rules();
+    self.playfield = Playfield::new(seed, rules);
+    self.combo = 0;
+    self.combo_lines = 0;
+    self.score = 0;
+    self.b2b = 0;
+    self.message = LineClearMessage::empty();
+    self.lines = 0;
+    self.level = level::first_level(rules.style);
+    self.incoming_garbage =
+      GarbageQueue::new(seed, rules.board.width as usize, rules.garbage_messiness);
+    self.outgoing_garbage = 0;
+    self.stats = Stats::new();
+    self.finesse_fault = None;
+  }
+
+  pub fn has_lost(&self) -> bool {
+    self.playfield.has_lost()
+  }
+
+  /// The seed of the piece sequence, enough to replay the same pieces in a new game.
+  pub fn seed(&self) -> u64 {
+    self.playfield.next_queue().seed()
+  }
+
+  pub fn playfield(&self) -> &Playfield {
+    &self.playfield
+  }
+
+  pub fn score(&self) -> u64 {
+    self.score
+  }
+
+  /// The number of lines cleared so far.
+  pub fn lines(&self) -> u32 {
+    self.lines
+  }
+
+  pub fn level(&self) -> u32 {
+    self.level
+  }
+
+  pub fn stats(&self) -> &Stats {
+    &self.stats
+  }
+
+  /// The number of consecutive piece placements that cleared lines.
+  pub fn combo(&self) -> u32 {
+    self.combo
+  }
+
+  /// The number of consecutive difficult clears. Back-to-back bonuses apply from the second one.
+  pub fn b2b(&self) -> u32 {
+    self.b2b
+  }
+
+  pub fn message(&self) -> &LineClearMessage {
+    &self.message
+  }
+
+  /// The last finesse fault, with how much longer it stays on screen.
+  pub fn finesse_fault(&self) -> Option<(Finesse, Duration)> {
+    self.finesse_fault
+  }
+
+  /// Queues an attack from the opponent. It enters the board on the next lock that clears no
+  /// lines, unless outgoing attacks cancel it first.
+  pub fn receive_garbage(&mut self, lines: u32) {
+    self.incoming_garbage.push(lines);
+  }
+
+  /// Sets the board up as [`Playfield::set_up`] does.
+  pub fn set_up(
+    &mut self,
+    rows: &[Vec<Option<Mino>>],
+    pieces: &[Tetromino],
+    hold: Option<Tetromino>,
+  ) {
+    self.playfield.set_up(rows, pieces, hold);
+  }
+
+  /// Sets the board up as [`Playfield::set_up_fixed`] does.
+  pub fn set_up_fixed(
+    &mut self,
+    rows: &[Vec<Option<Mino>>],
+    pieces: &[Tetromino],
+    hold: Option<Tetromino>,
+  ) {
+    self.playfield.set_up_fixed(rows, pieces, hold);
+  }
+
+  /// Puts garbage rows on the board right away, as [`Playfield::add_garbage`] does.
+  pub fn add_garbage(&mut self, holes: &[usize]) {
+    self.playfield.add_garbage(holes);
+  }
+
+  /// The lines waiting to enter the board.
+  pub fn pending_garbage(&self) -> u32 {
+    self.incoming_garbage.pending()
+  }
+
+  /// Takes the lines sent since the last call, left over after cancelling incoming garbage.
+  pub fn take_outgoing_garbage(&mut self) -> u32 {
+    std::mem::take(&mut self.outgoing_garbage)
+  }
+
+  fn update_score(&mut self, update_info: &UpdateInfo) {
+    let UpdateInfo {
+      cleared_lines,
+      spin,
+      is_all_clear,
+      ..
+    } = *update_info;
+
+    let message = match cleared_lines {
+      0 => None,
+      1 => Some(MessageType::Single),
+      2 => Some(MessageType::Double),
+      3 => Some(MessageType::Triple),
+      4 => Some(MessageType::Tetris),
+      _ => panic!("cleared more than 4 lines at the same time"),
+    };
+
+    match self.playfield.rules().style {
+      Style::Guideline => {}
+      Style::Classic => {
+        self.update_classic_score(message, cleared_lines);
+        self.level = level::classic_level(self.lines);
+        return;
+      }
+      Style::Master => {
+        self.update_master_score(message, cleared_lines, is_all_clear);
+        self.level = level::master_level(self.level, cleared_lines);
+        return;
+      }
+    }
+
+    self.message = LineClearMessage::new(message, spin);
+    // Clears score at the level they were made on, even if they level up
+    let level = self.level() as u64;
+    self.lines += cleared_lines;
+    self.level = level::level(self.lines);
+
+    if cleared_lines == 0 {
+      self.combo = 0;
+    } else {
+      self.combo += 1;
+      if cleared_lines == 4 || spin.is_some() {
+        self.b2b += 1;
+      } else {
+        self.b2b = 0;
+      }
+    }
+
+    self.score += (self.combo * 50) as u64 * level;
+    let b2b_factor = if self.b2b >= 2 { 3 } else { 2 };
+    // T pieces never clear more than two lines with a mini or three with a full spin, so the
+    // cells past those only score the spins of other pieces.
+    const SCORE_TABLE: [[u64; 5]; 3] = [
+      /* cleared:  0   1    2    3    4  */
+      /*NoSpin */ [0, 100, 300, 500, 800],
+      /*Mini   */ [100, 200, 400, 500, 800],
+      /*Proper */ [400, 800, 1200, 1600, 2000],
+    ];
+    let spin_index = match spin.map(|(_, spin_type)| spin_type) {
+      None => 0,
+      Some(SpinType::Mini) => 1,
+      Some(SpinType::Proper) => 2,
+    };
+    self.score += b2b_factor * SCORE_TABLE[spin_index][cleared_lines as usize] * level / 2;
+
+    if is_all_clear {
+      self.message.message = Some(MessageType::AllClear);
+      self.score += 3500 * b2b_factor * level / 2;
+    }
+  }
+
+  /// Scores a clear with the NES table, which knows nothing of spins, combos, back-to-backs or all
+  /// clears.
+  fn update_classic_score(&mut self, message: Option<MessageType>, cleared_lines: u32) {
+    self.message = LineClearMessage::new(message, None);
+    let level = self.level() as u64;
+    self.lines += cleared_lines;
+
+    const SCORE_TABLE: [u64; 5] = [0, 40, 100, 300, 1200];
+    self.score += SCORE_TABLE[cleared_lines as usize] * (level + 1);
+  }
+
+  /// Scores a clear the way the first Tetris The Grand Master does: by level and lines, times a
+  /// combo bonus that grows with every line past the first of each clear, times four for an all
+  /// clear. Spins and back-to-backs are worth nothing.
+  fn update_master_score(
+    &mut self,
+    message: Option<MessageType>,
+    cleared_lines: u32,
+    is_all_clear: bool,
+  ) {
+    self.message = LineClearMessage::new(message, None);
+    let level = self.level as u64;
+    self.lines += cleared_lines;
+
+    if cleared_lines == 0 {
+      self.combo = 0;
+      self.combo_lines = 0;
+      return;
+    }
+
+    self.combo += 1;
+    self.combo_lines += cleared_lines;
+    let combo_bonus = 1 + 2 * (self.combo_lines - self.combo) as u64;
+    let lines = cleared_lines as u64;
+    let mut score = (level + lines).div_ceil(4) * lines * combo_bonus;
+    if is_all_clear {
+      self.message.message = Some(MessageType::AllClear);
+      score *= 4;
+    }
+    self.score += score;
+  }
+
+  /// Sends an attack for a clear, or lets pending garbage in when nothing was cleared. Returns the
+  /// lines sent.
+  fn update_garbage(&mut self, update_info: &UpdateInfo) -> u32 {
+    if update_info.cleared_lines == 0 {
+      let holes = self.incoming_garbage.take(GARBAGE_CAP);
+      self.playfield.add_garbage(&holes);
+      0
+    } else {
+      let attack = garbage::attack(update_info, self.combo, self.b2b);
+      self.outgoing_garbage += self.incoming_garbage.cancel(attack);
+      attack
+    }
+  }
+}
@@ -0,0 +1,125 @@
+//! Lines sent between players: how many a clear is worth and the ones waiting to be received.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::{line_clear_message::SpinType, playfield::UpdateInfo};
+
+/// The most garbage rows that enter the board on a single lock.
+pub const GARBAGE_CAP: u32 = 8;
+
+/// The number of garbage lines a clear sends, given the combo and back-to-back counters right
+/// after it.
+pub fn attack(update_info: &UpdateInfo, combo: u32, b2b: u32) -> u32 {
+  let UpdateInfo {
+    cleared_lines,
+    spin,
+    is_all_clear,
+    ..
+  } = *update_info;
+
+  if cleared_lines == 0 {
+    return 0;
+  }
+
+  const ATTACK_TABLE: [[u32; 5]; 3] = [
+    /* cleared:  0  1  2  3  4 */
+    /*NoSpin */ [0, 0, 1, 2, 4],
+    /*Mini   */ [0, 0, 1, 2, 4],
+    /*Proper */ [0, 2, 4, 6, 8],
+  ];
+  const COMBO_TABLE: [u32; 11] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5];
+
+  let spin_index = match spin.map(|(_, spin_type)| spin_type) {
+    None => 0,
+    Some(SpinType::Mini) => 1,
+    Some(SpinType::Proper) => 2,
+  };
+  let mut lines = ATTACK_TABLE[spin_index][cleared_lines as usize];
+
+  let ren = (combo.saturating_sub(1) as usize).min(COMBO_TABLE.len() - 1);
+  lines += COMBO_TABLE[ren];
+  if b2b >= 2 {
+    lines += 1;
+  }
+  if is_all_clear {
+    lines += 10;
+  }
+  lines
+}
+
+/// Incoming attacks that have not reached the board yet.
+///
+/// Every attack gets its own hole column. With a `messiness` above zero, each row after the first
+/// may move the hole somewhere else, that many times out of a hundred.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GarbageQueue {
+  pending: VecDeque<(u32, usize)>,
+  width: usize,
+  messiness: u8,
+  rng: ChaCha8Rng,
+}
+
+impl GarbageQueue {
+  /// Creates an empty queue for a board `width` columns wide.
+  pub fn new(seed: u64, width: usize, messiness: u8) -> Self {
+    Self {
+      pending: VecDeque::new(),
+      width,
+      messiness,
+      rng: ChaCha8Rng::seed_from_u64(seed),
+    }
+  }
+
+  pub fn push(&mut self, lines: u32) {
+    if lines > 0 {
+      let hole = self.rng.random_range(0..self.width);
+      self.pending.push_back((lines, hole));
+    }
+  }
+
+  /// The total number of lines waiting.
+  pub fn pending(&self) -> u32 {
+    self.pending.iter().map(|&(lines, _)| lines).sum()
+  }
+
+  /// Cancels the oldest pending lines with an outgoing `attack`, returning what is left to send.
+  pub fn cancel(&mut self, mut attack: u32) -> u32 {
+    while attack > 0 {
+      let Some((lines, _)) = self.pending.front_mut() else {
+        break;
+      };
+
+      let cancelled = attack.min(*lines);
+      *lines -= cancelled;
+      attack -= cancelled;
+      if *lines == 0 {
+        self.pending.pop_front();
+      }
+    }
+    attack
+  }
+
+  /// Takes up to `max_lines` pending lines, returning the hole column of each row from the top
+  /// one down.
+  pub fn take(&mut self, max_lines: u32) -> Vec<usize> {
+    let mut holes = Vec::new();
+    while (holes.len() as u32) < max_lines {
+      let Some((lines, hole)) = self.pending.front_mut() else {
+        break;
+      };
+
+      holes.push(*hole);
+
+      *lines -= 1;
+      if *lines == 0 {
+        self.pending.pop_front();
+      } else if self.rng.random_range(0..100) < self.messiness {
+        *hole = self.rng.random_range(0..self.width);
+      }
+    }
+    holes
+  }
+}
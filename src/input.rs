@@ -0,0 +1,85 @@
+//! What a player wants to do on a given frame, independent of how they asked for it.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+  Swap,
+  Left,
+  Right,
+  Clockwise,
+  CounterClockwise,
+  OneEighty,
+  HardDrop,
+  SoftDrop,
+}
+
+impl Action {
+  fn bit(self) -> u8 {
+    1 << self as u8
+  }
+}
+
+/// The actions pressed on this frame and the ones held down during it.
+///
+/// A key that goes down on this frame is usually both pressed and held.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub struct Input {
+  pressed: u8,
+  held: u8,
+}
+
+impl Input {
+  pub fn press(&mut self, action: Action) {
+    self.pressed |= action.bit();
+  }
+
+  pub fn hold(&mut self, action: Action) {
+    self.held |= action.bit();
+  }
+
+  pub fn is_pressed(self, action: Action) -> bool {
+    self.pressed & action.bit() != 0
+  }
+
+  pub fn is_held(self, action: Action) -> bool {
+    self.held & action.bit() != 0
+  }
+
+  /// The same input with `action` neither pressed nor held.
+  pub fn without(mut self, action: Action) -> Self {
+    self.pressed &= !action.bit();
+    self.held &= !action.bit();
+    self
+  }
+
+  /// The same input with nothing held.
+  pub fn pressed_only(mut self) -> Self {
+    self.held = 0;
+    self
+  }
+
+  /// The same input with nothing pressed.
+  pub fn held_only(mut self) -> Self {
+    self.pressed = 0;
+    self
+  }
+
+  /// The actions pressed or held in either input.
+  pub fn merge(self, other: Self) -> Self {
+    Self {
+      pressed: self.pressed | other.pressed,
+      held: self.held | other.held,
+    }
+  }
+
+  /// The number of actions pressed.
+  pub fn presses(self) -> u32 {
+    self.pressed.count_ones()
+  }
+
+  /// Whether nothing at all was pressed or held.
+  pub fn is_empty(self) -> bool {
+    self.pressed == 0 && self.held == 0
+  }
+}
@@ -0,0 +1,129 @@
+//! How fast pieces fall and lock as the level goes up.
+
+use std::time::Duration;
+
+use crate::rules::Style;
+
+pub const LINES_PER_LEVEL: u32 = 10;
+/// From this level on, pieces reach the bottom as soon as they spawn.
+pub const TWENTY_G_LEVEL: u32 = 20;
+
+/// The level a game of `style` starts on.
+pub fn first_level(style: Style) -> u32 {
+  match style {
+    Style::Guideline => 1,
+    Style::Classic | Style::Master => 0,
+  }
+}
+
+/// The level reached after clearing `lines`, starting at 1.
+pub fn level(lines: u32) -> u32 {
+  1 + lines / LINES_PER_LEVEL
+}
+
+/// The time a piece takes to fall one row at `level`, following the Guideline curve
+/// `(0.8 - (level - 1) * 0.007) ^ (level - 1)` seconds. It is zero, meaning instant, from
+/// [`TWENTY_G_LEVEL`] on.
+pub fn gravity(level: u32) -> Duration {
+  if level >= TWENTY_G_LEVEL {
+    return Duration::ZERO;
+  }
+
+  let steps = level.saturating_sub(1) as f64;
+  Duration::from_secs_f64((0.8 - steps * 0.007).powf(steps))
+}
+
+/// The level reached after clearing `lines` in the classic style, starting at 0.
+pub fn classic_level(lines: u32) -> u32 {
+  lines / LINES_PER_LEVEL
+}
+
+/// The time a piece takes to fall one row at a classic `level`, from the frame counts of the NES
+/// game.
+pub fn classic_gravity(level: u32) -> Duration {
+  const NES_FRAME_RATE: f64 = 60.0988;
+  const FRAMES_PER_ROW: [u32; 30] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6, 5, 5, 5, 4, 4, 4, 3, 3, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+    1,
+  ];
+
+  let frames = FRAMES_PER_ROW[(level as usize).min(FRAMES_PER_ROW.len() - 1)];
+  Duration::from_secs_f64(frames as f64 / NES_FRAME_RATE)
+}
+
+/// The longest a piece may rest on the stack at `level`. It starts shrinking once gravity is
+/// instant, as the only way left to make the game harder.
+pub fn lock_delay(level: u32) -> Duration {
+  const LONGEST: Duration = Duration::from_millis(500);
+  const SHORTEST: Duration = Duration::from_millis(200);
+  const STEP: Duration = Duration::from_millis(25);
+
+  let shortened = level.saturating_sub(TWENTY_G_LEVEL);
+  LONGEST.saturating_sub(STEP * shortened).max(SHORTEST)
+}
+
+/// The last level of the master style, which ends the game.
+pub const MASTER_LAST_LEVEL: u32 = 999;
+pub const MASTER_LOCK_DELAY: Duration = Duration::from_millis(500);
+pub const MASTER_ENTRY_DELAY: Duration = Duration::from_millis(500);
+pub const MASTER_LINE_CLEAR_DELAY: Duration = Duration::from_millis(683);
+
+/// The master level after a lock at `level` that cleared `lines`. Every piece raises it by one
+/// and every line by one more, but pieces alone never take it past the end of a section, the
+/// levels ending in 99, nor past the one before [`MASTER_LAST_LEVEL`].
+pub fn master_level(level: u32, lines: u32) -> u32 {
+  let level = level + lines;
+  let is_stopped = level % 100 == 99 || level == MASTER_LAST_LEVEL - 1;
+  let level = if is_stopped { level } else { level + 1 };
+  level.min(MASTER_LAST_LEVEL)
+}
+
+/// The time a piece takes to fall one row at a master `level`, following the first Tetris The
+/// Grand Master. Gravity there is counted in 256ths of a row per frame, and reaches 20G at level
+/// 500.
+pub fn master_gravity(level: u32) -> Duration {
+  const FRAME_RATE: f64 = 60.0;
+  const TWENTY_G: u32 = 20 * 256;
+  const GRAVITY_TABLE: [(u32, u32); 30] = [
+    (0, 4),
+    (30, 6),
+    (35, 8),
+    (40, 10),
+    (50, 12),
+    (60, 16),
+    (70, 32),
+    (80, 48),
+    (90, 64),
+    (100, 80),
+    (120, 96),
+    (140, 112),
+    (160, 128),
+    (170, 144),
+    (200, 4),
+    (220, 32),
+    (230, 64),
+    (233, 96),
+    (236, 128),
+    (239, 160),
+    (243, 192),
+    (247, 224),
+    (251, 256),
+    (300, 512),
+    (330, 768),
+    (360, 1024),
+    (400, 1280),
+    (420, 1024),
+    (450, 768),
+    (500, TWENTY_G),
+  ];
+
+  let (_, gravity) = GRAVITY_TABLE
+    .iter()
+    .rev()
+    .find(|&&(from_level, _)| level >= from_level)
+    .expect("the table should start at level 0");
+  if *gravity >= TWENTY_G {
+    return Duration::ZERO;
+  }
+  Duration::from_secs_f64(256.0 / (*gravity as f64 * FRAME_RATE))
+}
@@ -0,0 +1,76 @@
+//! The rules engine behind RAYTRIS.
+//!
+//! Nothing in this crate depends on a window or an input library. A front end turns its key
+//! presses into an [`input::Input`] every frame and hands it, together with the elapsed time, to
+//! [`game::Game::update`] (or straight to [`playfield::Playfield::update`] when it does not care
+//! about scoring). The `raylib` feature, enabled by default, only builds the game binary on top.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use rustris::{
+//!   HandlingSettings,
+//!   game::Game,
+//!   input::{Action, Input},
+//!   rules::Rules,
+//! };
+//!
+//! let mut game = Game::new(0, Rules::default());
+//! let mut input = Input::default();
+//! input.press(Action::HardDrop);
+//!
+//! let update_info = game.update(Duration::from_millis(16), input, &HandlingSettings::default());
+//! assert_eq!(update_info.map(|info| info.cleared_lines), Some(0));
+//! ```
+
+pub mod fumen;
+pub mod game;
+pub mod garbage;
+pub mod input;
+pub mod level;
+pub mod line_clear_message;
+pub mod mode;
+pub mod playfield;
+pub mod replay;
+pub mod rules;
+pub mod solver;
+pub mod stats;
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Timings that depend on the player rather than on the game being played.
+///
+/// [`game::Game`] replaces `gravity` with the one of its level and never lets `lock_delay` get
+/// longer than the level allows. It also sets both entry delays under the master style.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HandlingSettings {
+  pub gravity: Duration,
+  pub soft_drop: Duration,
+  pub das: Duration,
+  /// The time between auto repeated shifts once DAS charges. Zero moves the piece straight to the
+  /// wall.
+  pub arr: Duration,
+  pub lock_delay: Duration,
+  pub lock_delay_resets: u32,
+  /// The pause between a piece locking and the next one entering, known as ARE.
+  pub entry_delay: Duration,
+  /// Added to the entry delay when the lock cleared lines.
+  pub line_clear_delay: Duration,
+}
+
+impl Default for HandlingSettings {
+  fn default() -> Self {
+    Self {
+      gravity: Duration::from_millis(330),
+      soft_drop: Duration::from_millis(160),
+      das: Duration::from_millis(140),
+      arr: Duration::ZERO,
+      lock_delay: Duration::from_millis(500),
+      lock_delay_resets: 15,
+      entry_delay: Duration::ZERO,
+      line_clear_delay: Duration::ZERO,
+    }
+  }
+}
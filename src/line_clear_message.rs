@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::playfield::tetromino::Tetromino;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum MessageType {
+  Single,
+  Double,
+  Triple,
+  Tetris,
+  AllClear,
+}
+
+impl MessageType {
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Single => "SINGLE",
+      Self::Double => "DOUBLE",
+      Self::Triple => "TRIPLE",
+      Self::Tetris => "TETRIS",
+      Self::AllClear => "ALL\nCLEAR",
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum SpinType {
+  Mini,
+  Proper,
+}
+
+/// The last clear, kept around for [`LineClearMessage::DURATION`] so it can be shown.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LineClearMessage {
+  pub message: Option<MessageType>,
+  pub remaining_time: Duration,
+  pub spin: Option<(Tetromino, SpinType)>,
+}
+
+impl LineClearMessage {
+  pub const DURATION: Duration = Duration::from_secs(2);
+
+  pub fn empty() -> Self {
+    Self {
+      message: None,
+      remaining_time: Duration::ZERO,
+      spin: None,
+    }
+  }
+
+  pub fn new(message: Option<MessageType>, spin: Option<(Tetromino, SpinType)>) -> Self {
+    Self {
+      message,
+      remaining_time: Self::DURATION,
+      spin,
+    }
+  }
+
+  /// The name of the clear, such as "T-SPIN MINI DOUBLE", if the lock made one.
+  pub fn clear_name(&self) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some((tetromino, spin_type)) = self.spin {
+      parts.push(format!("{}-SPIN", tetromino.name()));
+      if spin_type == SpinType::Mini {
+        parts.push("MINI".to_string());
+      }
+    }
+    if let Some(message) = self.message {
+      parts.push(message.name().replace('\n', " "));
+    }
+
+    (!parts.is_empty()).then(|| parts.join(" "))
+  }
+}
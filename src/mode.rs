@@ -0,0 +1,217 @@
+//! Objectives played on top of a [`Game`], each deciding when it is over and what it keeps track
+//! of along the way.
+
+pub mod cheese;
+pub mod master;
+pub mod pc_training;
+pub mod puzzle;
+pub mod sprint;
+pub mod survival;
+pub mod ultra;
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{HandlingSettings, game::Game, input::Input, rules::Rules};
+
+use cheese::{Cheese, CheeseGoal};
+use master::Master;
+use pc_training::{PcStart, PcTraining};
+use puzzle::{Puzzle, Puzzles};
+use sprint::{Sprint, SprintGoal};
+use survival::Survival;
+use ultra::{Ultra, UltraLimit};
+
+/// The single player modes, as picked from the main menu.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum ModeKind {
+  #[default]
+  Marathon,
+  Sprint,
+  Ultra,
+  Cheese,
+  Survival,
+  Classic,
+  Master,
+  Puzzle,
+  PcTraining,
+}
+
+impl ModeKind {
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Marathon => "Marathon",
+      Self::Sprint => "Sprint",
+      Self::Ultra => "Ultra",
+      Self::Cheese => "Cheese",
+      Self::Survival => "Survival",
+      Self::Classic => "Classic",
+      Self::Master => "Master",
+      Self::Puzzle => "Puzzle",
+      Self::PcTraining => "PC Training",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::Marathon => Self::Sprint,
+      Self::Sprint => Self::Ultra,
+      Self::Ultra => Self::Cheese,
+      Self::Cheese => Self::Survival,
+      Self::Survival => Self::Classic,
+      Self::Classic => Self::Master,
+      Self::Master => Self::Puzzle,
+      Self::Puzzle => Self::PcTraining,
+      Self::PcTraining => Self::Marathon,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::Marathon => Self::PcTraining,
+      Self::Sprint => Self::Marathon,
+      Self::Ultra => Self::Sprint,
+      Self::Cheese => Self::Ultra,
+      Self::Survival => Self::Cheese,
+      Self::Classic => Self::Survival,
+      Self::Master => Self::Classic,
+      Self::Puzzle => Self::Master,
+      Self::PcTraining => Self::Puzzle,
+    }
+  }
+}
+
+/// A mode together with its progress.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Mode {
+  /// Endless play, only over when topping out.
+  Marathon,
+  Sprint(Sprint),
+  Ultra(Ultra),
+  /// A race through garbage rows.
+  Cheese(Box<Cheese>),
+  /// Endless play with garbage rising faster and faster.
+  Survival(Box<Survival>),
+  /// Endless play under the NES rules, whatever the configured ones.
+  Classic,
+  /// A climb to 20G and beyond under the Grand Master rules, ending with a grade.
+  Master(Master),
+  /// Predefined boards with a goal to reach.
+  Puzzle(Box<Puzzles>),
+  /// Perfect clears in a row, started over when the board can no longer be cleared.
+  PcTraining(Box<PcTraining>),
+}
+
+impl Mode {
+  /// The rules to play the mode under, given the configured `rules`.
+  pub fn rules(&self, rules: Rules) -> Rules {
+    match self {
+      Self::Classic => Rules::classic(),
+      Self::Master(_) => Rules::master(),
+      Self::Puzzle(puzzles) => Rules {
+        board: puzzles.puzzle().board(),
+        ..rules
+      },
+      _ => rules,
+    }
+  }
+
+  /// Advances `game` by a frame under the rules of the mode, returning whether a piece locked.
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    match self {
+      Self::Marathon | Self::Classic => game.update(dt, input, settings).is_some(),
+      Self::Sprint(sprint) => sprint.update(game, dt, input, settings),
+      Self::Ultra(ultra) => ultra.update(game, dt, input, settings),
+      Self::Cheese(cheese) => cheese.update(game, dt, input, settings),
+      Self::Survival(survival) => survival.update(game, dt, input, settings),
+      Self::Master(master) => master.update(game, dt, input, settings),
+      Self::Puzzle(puzzles) => puzzles.update(game, dt, input, settings),
+      Self::PcTraining(training) => training.update(game, dt, input, settings),
+    }
+  }
+
+  /// Whether the run is over and has results to show: the objective was met, the time ran out,
+  /// in survival the board topped out, or a puzzle was solved or failed.
+  pub fn is_finished(&self) -> bool {
+    match self {
+      Self::Marathon | Self::Classic | Self::PcTraining(_) => false,
+      Self::Sprint(sprint) => sprint.is_finished(),
+      Self::Ultra(ultra) => ultra.is_finished(),
+      Self::Cheese(cheese) => cheese.is_finished(),
+      Self::Survival(survival) => survival.is_finished(),
+      Self::Master(master) => master.is_finished(),
+      Self::Puzzle(puzzles) => puzzles.is_finished(),
+    }
+  }
+
+  /// Whether taking moves back is fair, which it is not when racing against the clock.
+  pub fn allows_undo(&self) -> bool {
+    matches!(self, Self::Marathon | Self::Classic)
+  }
+
+  /// What the mode started from. Puzzles only keep the current puzzle.
+  pub fn setup(&self) -> ModeSetup {
+    match self {
+      Self::Marathon => ModeSetup::Marathon,
+      Self::Sprint(sprint) => ModeSetup::Sprint(sprint.goal()),
+      Self::Ultra(ultra) => ModeSetup::Ultra(ultra.limit()),
+      Self::Cheese(cheese) => ModeSetup::Cheese(cheese.goal()),
+      Self::Survival(_) => ModeSetup::Survival,
+      Self::Classic => ModeSetup::Classic,
+      Self::Master(_) => ModeSetup::Master,
+      Self::Puzzle(puzzles) => ModeSetup::Puzzle(Box::new(puzzles.puzzle().clone())),
+      Self::PcTraining(training) => ModeSetup::PcTraining(training.start()),
+    }
+  }
+
+  /// Clears the progress so the mode can be played again.
+  pub fn reset(&mut self) {
+    match self {
+      Self::Marathon | Self::Classic => {}
+      Self::Sprint(sprint) => *sprint = Sprint::new(sprint.goal()),
+      Self::Ultra(ultra) => *ultra = Ultra::new(ultra.limit()),
+      Self::Cheese(cheese) => **cheese = Cheese::new(cheese.goal()),
+      Self::Survival(survival) => **survival = Survival::new(),
+      Self::Master(master) => *master = Master::new(),
+      Self::Puzzle(puzzles) => puzzles.restart(),
+      Self::PcTraining(training) => **training = PcTraining::new(training.start()),
+    }
+  }
+}
+
+/// Everything a mode needs to start, kept to play the same mode again.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum ModeSetup {
+  Marathon,
+  Sprint(SprintGoal),
+  Ultra(UltraLimit),
+  Cheese(CheeseGoal),
+  Survival,
+  Classic,
+  Master,
+  Puzzle(Box<Puzzle>),
+  PcTraining(PcStart),
+}
+
+impl ModeSetup {
+  /// The mode, with no progress made yet.
+  pub fn mode(&self) -> Mode {
+    match self {
+      Self::Marathon => Mode::Marathon,
+      Self::Sprint(goal) => Mode::Sprint(Sprint::new(*goal)),
+      Self::Ultra(limit) => Mode::Ultra(Ultra::new(*limit)),
+      Self::Cheese(goal) => Mode::Cheese(Box::new(Cheese::new(*goal))),
+      Self::Survival => Mode::Survival(Box::default()),
+      Self::Classic => Mode::Classic,
+      Self::Master => Mode::Master(Master::new()),
+      Self::Puzzle(puzzle) => Mode::Puzzle(Box::new(Puzzles::new(vec![(**puzzle).clone()]))),
+      Self::PcTraining(start) => Mode::PcTraining(Box::new(PcTraining::new(*start))),
+    }
+  }
+}
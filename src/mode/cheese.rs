@@ -0,0 +1,190 @@
+//! Digging through a set number of garbage lines as fast as possible.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{HandlingSettings, game::Game, garbage::GarbageQueue, input::Input};
+
+/// The most garbage rows on the board at once. Cleared rows are replaced from below until the
+/// goal is in reach.
+pub const CHEESE_HEIGHT: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum CheeseGoal {
+  #[default]
+  Ten,
+  Eighteen,
+  Hundred,
+}
+
+impl CheeseGoal {
+  pub fn lines(self) -> u32 {
+    match self {
+      Self::Ten => 10,
+      Self::Eighteen => 18,
+      Self::Hundred => 100,
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Ten => "10 Lines",
+      Self::Eighteen => "18 Lines",
+      Self::Hundred => "100 Lines",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::Ten => Self::Eighteen,
+      Self::Eighteen => Self::Hundred,
+      Self::Hundred => Self::Ten,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::Ten => Self::Hundred,
+      Self::Eighteen => Self::Ten,
+      Self::Hundred => Self::Eighteen,
+    }
+  }
+}
+
+/// The garbage left to dig and the clock of a cheese race. The messiness of the rules decides how
+/// often the hole moves from one row to the next, and like a sprint the clock starts with the first
+/// input.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Cheese {
+  goal: CheeseGoal,
+  /// Created along with the first rows, seeded from the game so the same seed digs the same
+  /// garbage.
+  garbage: Option<GarbageQueue>,
+  added_lines: u32,
+  time: Option<Duration>,
+  cleared_lines: u32,
+}
+
+impl Cheese {
+  pub fn new(goal: CheeseGoal) -> Self {
+    Self {
+      goal,
+      garbage: None,
+      added_lines: 0,
+      time: None,
+      cleared_lines: 0,
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if self.garbage.is_none() {
+      self.refill(game);
+    }
+    if self.is_finished() || game.has_lost() {
+      return false;
+    }
+
+    let (dt, time) = match self.time {
+      Some(time) => (dt, time + dt),
+      None if input.is_empty() => return false,
+      None => (Duration::ZERO, Duration::ZERO),
+    };
+    self.time = Some(time);
+
+    let locked = game.update(dt, input, settings).is_some();
+    if locked {
+      // Garbage rows only leave the board by being cleared
+      self.cleared_lines = self.added_lines - game.playfield().garbage_rows() as u32;
+      self.refill(game);
+    }
+    locked
+  }
+
+  /// Tops the board back up to [`CHEESE_HEIGHT`] garbage rows, without adding more than the goal.
+  fn refill(&mut self, game: &mut Game) {
+    let garbage = self.garbage.get_or_insert_with(|| {
+      let playfield = game.playfield();
+      let mut garbage = GarbageQueue::new(
+        game.seed(),
+        playfield.size().width as usize,
+        playfield.rules().garbage_messiness,
+      );
+      // A single attack, so that the messiness decides how often the hole moves between rows
+      garbage.push(self.goal.lines());
+      garbage
+    });
+
+    let on_board = self.added_lines - self.cleared_lines;
+    let holes = garbage.take(CHEESE_HEIGHT - on_board);
+    self.added_lines += holes.len() as u32;
+    game.add_garbage(&holes);
+  }
+
+  pub fn goal(&self) -> CheeseGoal {
+    self.goal
+  }
+
+  pub fn has_started(&self) -> bool {
+    self.time.is_some()
+  }
+
+  pub fn time(&self) -> Duration {
+    self.time.unwrap_or_default()
+  }
+
+  /// The garbage lines dug out so far.
+  pub fn cleared_lines(&self) -> u32 {
+    self.cleared_lines
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.cleared_lines == self.goal.lines()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rules::Rules;
+
+  /// The hole column of every garbage row on a fresh cheese board, from the top one down.
+  fn holes(garbage_messiness: u8) -> Vec<Option<usize>> {
+    let rules = Rules {
+      garbage_messiness,
+      ..Rules::default()
+    };
+    let mut game = Game::new(0, rules);
+    let mut cheese = Cheese::new(CheeseGoal::Ten);
+    cheese.update(
+      &mut game,
+      Duration::ZERO,
+      Input::default(),
+      &HandlingSettings::default(),
+    );
+
+    game.playfield().grid()[..CHEESE_HEIGHT as usize]
+      .iter()
+      .map(|row| row.iter().position(Option::is_none))
+      .collect()
+  }
+
+  #[test]
+  fn tidy_garbage_keeps_one_column() {
+    let holes = holes(0);
+    assert!(holes[0].is_some());
+    assert!(holes.iter().all(|&hole| hole == holes[0]));
+  }
+
+  #[test]
+  fn messy_garbage_moves_the_hole() {
+    let holes = holes(100);
+    assert!(holes.iter().all(Option::is_some));
+    assert!(holes.iter().any(|&hole| hole != holes[0]));
+  }
+}
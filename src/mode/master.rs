@@ -0,0 +1,200 @@
+//! Climbing to the last level of the master style, graded on score and speed.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{HandlingSettings, game::Game, input::Input, level::MASTER_LAST_LEVEL};
+
+/// The levels in a section, each of which gets its own time.
+pub const SECTION_LEVELS: u32 = 100;
+
+/// The score needed for each grade below Grand Master, from the lowest one up.
+const GRADES: [(&str, u64); 18] = [
+  ("9", 0),
+  ("8", 400),
+  ("7", 800),
+  ("6", 1400),
+  ("5", 2000),
+  ("4", 3500),
+  ("3", 5500),
+  ("2", 8000),
+  ("1", 12000),
+  ("S1", 16000),
+  ("S2", 22000),
+  ("S3", 30000),
+  ("S4", 40000),
+  ("S5", 52000),
+  ("S6", 66000),
+  ("S7", 82000),
+  ("S8", 100000),
+  ("S9", 120000),
+];
+
+/// What it takes to become a Grand Master, on top of reaching the last level: the time by which
+/// levels 300 and 500 and the end must be reached, and the score needed when reaching them.
+const GRAND_MASTER_CHECKPOINTS: [(u32, Duration, u64); 3] = [
+  (300, Duration::from_secs(4 * 60 + 15), 12000),
+  (500, Duration::from_secs(7 * 60 + 30), 40000),
+  (MASTER_LAST_LEVEL, Duration::from_secs(13 * 60 + 30), 126000),
+];
+
+/// The clock of a master run, the time and score each section was cleared at and the grade earned.
+/// Like a sprint, the clock starts with the first input.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Master {
+  time: Option<Duration>,
+  section_times: Vec<Duration>,
+  #[serde(default)]
+  section_scores: Vec<u64>,
+  grade: String,
+  is_finished: bool,
+}
+
+impl Master {
+  pub fn new() -> Self {
+    let (grade, _) = GRADES[0];
+    Self {
+      time: None,
+      section_times: Vec::new(),
+      section_scores: Vec::new(),
+      grade: grade.to_string(),
+      is_finished: false,
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if self.is_finished || game.has_lost() {
+      return false;
+    }
+
+    let (dt, time) = match self.time {
+      Some(time) => (dt, time + dt),
+      None if input.is_empty() => return false,
+      None => (Duration::ZERO, Duration::ZERO),
+    };
+    self.time = Some(time);
+
+    let locked = game.update(dt, input, settings).is_some();
+    if locked {
+      let level = game.level();
+      while (self.section_times.len() as u32) < level / SECTION_LEVELS {
+        self.section_times.push(time);
+        self.section_scores.push(game.score());
+      }
+      self.is_finished = level == MASTER_LAST_LEVEL;
+      if self.is_finished {
+        self.section_times.push(time);
+        self.section_scores.push(game.score());
+      }
+      self.grade = self.grade_of(game.score()).to_string();
+    }
+    locked
+  }
+
+  pub fn has_started(&self) -> bool {
+    self.time.is_some()
+  }
+
+  pub fn time(&self) -> Duration {
+    self.time.unwrap_or_default()
+  }
+
+  /// The time at which every [`SECTION_LEVELS`] levels were reached, and the last level.
+  pub fn section_times(&self) -> &[Duration] {
+    &self.section_times
+  }
+
+  /// The grade earned so far.
+  pub fn grade(&self) -> &str {
+    &self.grade
+  }
+
+  /// The grade of a run that has scored `score` so far.
+  fn grade_of(&self, score: u64) -> &'static str {
+    let meets_checkpoints = GRAND_MASTER_CHECKPOINTS
+      .iter()
+      .all(|&(level, limit, needed)| {
+        self
+          .reached_at(level, score)
+          .is_some_and(|(time, score)| time <= limit && score >= needed)
+      });
+    if self.is_finished && meets_checkpoints {
+      return "GM";
+    }
+
+    let (grade, _) = GRADES
+      .iter()
+      .rev()
+      .find(|&&(_, needed)| score >= needed)
+      .expect("the lowest grade needs no score");
+    grade
+  }
+
+  /// The time and score `level` was reached at, if it is the end of a section or the last level,
+  /// the score at the last level being `score`.
+  fn reached_at(&self, level: u32, score: u64) -> Option<(Duration, u64)> {
+    if level == MASTER_LAST_LEVEL {
+      return self.is_finished.then(|| (self.time(), score));
+    }
+
+    let section = ((level / SECTION_LEVELS) as usize).checked_sub(1)?;
+    let time = self.section_times.get(section)?;
+    let score = self.section_scores.get(section)?;
+    Some((*time, *score))
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.is_finished
+  }
+}
+
+impl Default for Master {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A run that reached the end of every section 40 seconds after the one before, and the last
+  /// level 40 seconds after that, with `score_at` the score at each of those levels.
+  fn finished_run(score_at: impl Fn(u32) -> u64) -> Master {
+    let mut master = Master::new();
+    let sections = MASTER_LAST_LEVEL / SECTION_LEVELS;
+    for section in 1..=sections + 1 {
+      let level = (section * SECTION_LEVELS).min(MASTER_LAST_LEVEL);
+      master.section_times.push(Duration::from_secs(40) * section);
+      master.section_scores.push(score_at(level));
+    }
+    master.time = master.section_times.last().copied();
+    master.is_finished = true;
+    master
+  }
+
+  #[test]
+  fn fast_high_scoring_runs_are_grand_masters() {
+    let master = finished_run(|level| 150 * u64::from(level));
+    assert_eq!(master.grade_of(150 * u64::from(MASTER_LAST_LEVEL)), "GM");
+  }
+
+  #[test]
+  fn missing_a_score_checkpoint_is_no_grand_master() {
+    // Every time limit is met, but the score at level 500 is short of 40000
+    let master = finished_run(|level| {
+      if level == 500 {
+        30000
+      } else {
+        150 * u64::from(level)
+      }
+    });
+    assert_eq!(master.grade_of(150 * u64::from(MASTER_LAST_LEVEL)), "S9");
+  }
+}
@@ -0,0 +1,323 @@
+//! Practicing perfect clears, one after the other.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, iter, time::Duration};
+
+use crate::{
+  HandlingSettings,
+  game::Game,
+  input::Input,
+  playfield::{Grid, Mino, next_queue::NEXT_SIZE, tetromino::Tetromino},
+  solver::{self, MAX_PC_HEIGHT, Placement},
+};
+
+/// The most seeds tried when looking for pieces that can clear the board.
+const SEED_ATTEMPTS: u32 = 100;
+/// The most pieces already placed in the opener position.
+const OPENER_PIECES: usize = 6;
+
+/// The position each attempt starts from.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum PcStart {
+  #[default]
+  Empty,
+  /// The first bag already placed on the way to a perfect clear.
+  Opener,
+}
+
+impl PcStart {
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Empty => "Empty Board",
+      Self::Opener => "Opener",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::Empty => Self::Opener,
+      Self::Opener => Self::Empty,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    self.next()
+  }
+}
+
+/// Perfect clears in a row, with the pieces always able to make the next one. An attempt is
+/// started over as soon as the board can no longer be cleared with the upcoming pieces.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PcTraining {
+  start: PcStart,
+  /// Picks the seeds of the attempts, seeded from the game so the same seed plays the same ones.
+  rng: Option<ChaCha8Rng>,
+  /// The rest of the perfect clear last found, with the board and hold slot each placement leaves,
+  /// so that the board is only searched again once the player strays from it.
+  #[serde(skip)]
+  plan: VecDeque<(Placement, Grid)>,
+  is_set_up: bool,
+  streak: u32,
+  best_streak: u32,
+  perfect_clears: u32,
+  failures: u32,
+}
+
+impl PcTraining {
+  pub fn new(start: PcStart) -> Self {
+    Self {
+      start,
+      rng: None,
+      plan: VecDeque::new(),
+      is_set_up: false,
+      streak: 0,
+      best_streak: 0,
+      perfect_clears: 0,
+      failures: 0,
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if !self.is_set_up {
+      self.set_up(game);
+      self.is_set_up = true;
+    }
+
+    let Some(update_info) = game.update(dt, input, settings) else {
+      if game.has_lost() {
+        self.fail(game);
+      }
+      return false;
+    };
+
+    if update_info.is_all_clear {
+      self.streak += 1;
+      self.best_streak = self.best_streak.max(self.streak);
+      self.perfect_clears += 1;
+      if !self.can_clear(game) {
+        self.set_up(game);
+      }
+    } else if game.has_lost() || !self.can_clear(game) {
+      self.fail(game);
+    }
+    true
+  }
+
+  fn fail(&mut self, game: &mut Game) {
+    self.streak = 0;
+    self.failures += 1;
+    self.set_up(game);
+  }
+
+  /// Starts an attempt from the chosen position, on a seed whose pieces can clear the board.
+  fn set_up(&mut self, game: &mut Game) {
+    for _ in 0..SEED_ATTEMPTS {
+      let rng = self
+        .rng
+        .get_or_insert_with(|| ChaCha8Rng::seed_from_u64(game.seed()));
+      game.reset(rng.random());
+      if self.start == PcStart::Empty {
+        if self.can_clear(game) {
+          return;
+        }
+        continue;
+      }
+
+      // Openers lead to the tallest clear, so that the pieces already placed leave rows to fill
+      let playfield = game.playfield();
+      let width = playfield.size().width as usize;
+      let pieces = upcoming(game, MAX_PC_HEIGHT * width / 4 + 1);
+      let Some(solution) = solver::find_perfect_clear_of_height(
+        playfield.grid(),
+        &pieces,
+        playfield.holding_piece(),
+        MAX_PC_HEIGHT,
+      ) else {
+        continue;
+      };
+      let dealt = NEXT_SIZE + 2;
+      let opener: Vec<_> = solution
+        .into_iter()
+        .take(OPENER_PIECES)
+        .take_while(|placement| placement.pieces_used < dealt)
+        .collect();
+      let rows = place(width, &opener);
+      let Some(last) = opener.last() else {
+        continue;
+      };
+      if rows.iter().flatten().all(Option::is_none) {
+        continue;
+      }
+      game.set_up(&rows, &pieces[last.pieces_used..dealt], last.hold);
+      self.can_clear(game);
+      return;
+    }
+  }
+
+  /// Whether the board of `game` can still be cleared with its upcoming pieces. The plan is
+  /// followed as long as the last piece went where it said, and searched for again otherwise.
+  fn can_clear(&mut self, game: &Game) -> bool {
+    let playfield = game.playfield();
+    let followed = self.plan.front().is_some_and(|(placement, grid)| {
+      placement.hold == playfield.holding_piece() && grid == playfield.grid()
+    });
+    if followed {
+      self.plan.pop_front();
+      if !self.plan.is_empty() {
+        return true;
+      }
+    }
+
+    let Some(solution) = find_perfect_clear(game) else {
+      self.plan.clear();
+      return false;
+    };
+    let mut grid = playfield.grid().clone();
+    self.plan = solution
+      .into_iter()
+      .map(|placement| {
+        grid = play(&grid, &placement);
+        (placement, grid.clone())
+      })
+      .collect();
+    true
+  }
+
+  /// The perfect clears in a row of the current attempt.
+  pub fn streak(&self) -> u32 {
+    self.streak
+  }
+
+  pub fn best_streak(&self) -> u32 {
+    self.best_streak
+  }
+
+  pub fn perfect_clears(&self) -> u32 {
+    self.perfect_clears
+  }
+
+  /// The attempts started over because the board could no longer be cleared.
+  pub fn failures(&self) -> u32 {
+    self.failures
+  }
+
+  pub fn start(&self) -> PcStart {
+    self.start
+  }
+}
+
+/// The falling piece followed by the `count - 1` pieces after it.
+fn upcoming(game: &Game, count: usize) -> Vec<Tetromino> {
+  let playfield = game.playfield();
+  let mut next_queue = playfield.next_queue().clone();
+  iter::once(playfield.falling_piece().tetromino)
+    .chain(iter::from_fn(|| next_queue.next_tetromino()))
+    .take(count)
+    .collect()
+}
+
+/// A way to clear the board of `game` with its upcoming pieces, if there is one.
+fn find_perfect_clear(game: &Game) -> Option<Vec<Placement>> {
+  let playfield = game.playfield();
+  let width = playfield.size().width as usize;
+  // Enough pieces for the tallest clear, and one more to swap with the hold slot
+  let pieces = upcoming(game, MAX_PC_HEIGHT * width / 4 + 1);
+  solver::find_perfect_clear(playfield.grid(), &pieces, playfield.holding_piece())
+}
+
+/// `grid` after making `placement` on it and clearing the full rows.
+fn play(grid: &Grid, placement: &Placement) -> Grid {
+  let (width, height) = (grid[0].len(), grid.len());
+  let mut grid = grid.clone();
+  for &(x, y) in &placement.cells {
+    grid[y][x] = Some(Mino::Piece(placement.tetromino));
+  }
+  grid.retain(|row| row.iter().any(Option::is_none));
+  grid.resize(height, vec![None; width]);
+  grid
+}
+
+/// The rows, from the bottom one up, left by making `placements` on an empty board.
+fn place(width: usize, placements: &[Placement]) -> Grid {
+  let rows = vec![vec![None; width]; MAX_PC_HEIGHT];
+  placements
+    .iter()
+    .fold(rows, |rows, placement| play(&rows, placement))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rules::Rules;
+
+  fn start(start: PcStart, seed: u64) -> Game {
+    let mut game = Game::new(seed, Rules::default());
+    let mut pc_training = PcTraining::new(start);
+    pc_training.update(
+      &mut game,
+      Duration::ZERO,
+      Input::default(),
+      &HandlingSettings::default(),
+    );
+    game
+  }
+
+  #[test]
+  fn attempts_can_be_cleared() {
+    for seed in 0..5 {
+      let game = start(PcStart::Empty, seed);
+      assert!(
+        game
+          .playfield()
+          .grid()
+          .iter()
+          .flatten()
+          .all(Option::is_none)
+      );
+      assert!(find_perfect_clear(&game).is_some());
+    }
+  }
+
+  #[test]
+  fn openers_leave_rows_to_fill() {
+    for seed in 0..5 {
+      let game = start(PcStart::Opener, seed);
+      assert!(
+        game
+          .playfield()
+          .grid()
+          .iter()
+          .flatten()
+          .any(Option::is_some)
+      );
+      assert!(find_perfect_clear(&game).is_some());
+    }
+  }
+
+  #[test]
+  fn plans_are_only_searched_for_when_strayed_from() {
+    let mut game = Game::new(0, Rules::default());
+    let mut pc_training = PcTraining::new(PcStart::Empty);
+    pc_training.set_up(&mut game);
+    let (placement, grid) = pc_training.plan[0].clone();
+    let rest: Vec<_> = pc_training.plan.iter().skip(1).cloned().collect();
+    assert!(!rest.is_empty());
+
+    // Nothing but S pieces could never clear the board, so only the plan can tell it still can
+    game.set_up(&grid, &[Tetromino::S; 12], placement.hold);
+    assert!(pc_training.can_clear(&game));
+    assert!(pc_training.plan.iter().eq(&rest));
+
+    game.set_up(&[], &[Tetromino::S; 12], None);
+    assert!(!pc_training.can_clear(&game));
+    assert!(pc_training.plan.is_empty());
+  }
+}
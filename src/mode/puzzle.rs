@@ -0,0 +1,370 @@
+//! Predefined boards to solve with a fixed set of pieces.
+//!
+//! Puzzles are shared as JSON lists. Each puzzle has a name, the rows of its board from the top
+//! one down, its pieces in order, an optional piece in the hold slot and a goal:
+//!
+//! ```json
+//! [
+//!   {
+//!     "name": "First TSD",
+//!     "board": [
+//!       "GG...GGGGG",
+//!       "GGG.GGGGGG"
+//!     ],
+//!     "pieces": "T",
+//!     "hold": null,
+//!     "goal": { "TSpin": { "lines": 2 } }
+//!   }
+//! ]
+//! ```
+//!
+//! In board rows, `.` is an empty cell, `G` is garbage and the piece letters are minos of that
+//! piece. The board is as wide as its rows, or as the default board without any, and its rows sit
+//! at the bottom. The goal is either `"PerfectClear"`, `{ "TSpin": { "lines": n } }` or
+//! `{ "Lines": { "lines": n, "pieces": k } }`.
+
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, time::Duration};
+
+use crate::{
+  HandlingSettings,
+  game::Game,
+  input::Input,
+  line_clear_message::SpinType,
+  playfield::{BoardSize, Mino, UpdateInfo, tetromino::Tetromino},
+};
+
+/// What a puzzle asks for.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum PuzzleGoal {
+  /// Leave the board empty.
+  PerfectClear,
+  /// Clear `lines` lines at once with a T-spin that is not a mini.
+  TSpin { lines: u32 },
+  /// Clear `lines` lines in total with at most `pieces` pieces.
+  Lines { lines: u32, pieces: u32 },
+}
+
+impl PuzzleGoal {
+  pub fn name(self) -> String {
+    match self {
+      Self::PerfectClear => "Perfect clear".to_string(),
+      Self::TSpin { lines } => format!("T-spin clearing {lines} lines"),
+      Self::Lines { lines, pieces } => format!("Clear {lines} lines in {pieces} pieces"),
+    }
+  }
+}
+
+/// A puzzle as written in a file.
+#[derive(Deserialize)]
+struct PuzzleFile {
+  name: String,
+  board: Vec<String>,
+  pieces: String,
+  hold: Option<char>,
+  goal: PuzzleGoal,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Puzzle {
+  pub name: String,
+  /// The rows of the board from the bottom one up.
+  pub rows: Vec<Vec<Option<Mino>>>,
+  pub width: u32,
+  pub pieces: Vec<Tetromino>,
+  pub hold: Option<Tetromino>,
+  pub goal: PuzzleGoal,
+}
+
+impl Puzzle {
+  /// The board the puzzle is played on: as wide as its rows, and at least as tall as the
+  /// default one.
+  pub fn board(&self) -> BoardSize {
+    let default = BoardSize::default();
+    BoardSize {
+      width: self.width as i32,
+      visible_height: default.visible_height.max(self.rows.len() as i32),
+    }
+  }
+
+  /// The most pieces that can be placed, counting the one in the hold slot.
+  pub fn piece_limit(&self) -> u32 {
+    let available = self.pieces.len() as u32 + self.hold.is_some() as u32;
+    match self.goal {
+      PuzzleGoal::Lines { pieces, .. } => pieces.min(available),
+      _ => available,
+    }
+  }
+
+  fn from_file(file: PuzzleFile) -> Result<Self, PuzzleError> {
+    let PuzzleFile {
+      name,
+      board,
+      pieces,
+      hold,
+      goal,
+    } = file;
+
+    // Pieces need four columns to spawn
+    const NARROWEST: usize = 4;
+    let default_width = BoardSize::default().width as usize;
+    let width = board
+      .first()
+      .map_or(default_width, |row| row.chars().count());
+    if board.iter().any(|row| row.chars().count() != width) {
+      return Err(PuzzleError::UnevenBoard(name));
+    }
+    if width < NARROWEST {
+      return Err(PuzzleError::NarrowBoard(name));
+    }
+
+    let rows = board
+      .iter()
+      .rev()
+      .map(|row| row.chars().map(|cell| parse_cell(&name, cell)).collect())
+      .collect::<Result<_, _>>()?;
+    let pieces = pieces
+      .chars()
+      .map(|piece| parse_piece(&name, piece))
+      .collect::<Result<Vec<_>, _>>()?;
+    let hold = hold.map(|piece| parse_piece(&name, piece)).transpose()?;
+    if pieces.is_empty() {
+      return Err(PuzzleError::NoPieces(name));
+    }
+
+    Ok(Self {
+      name,
+      rows,
+      width: width as u32,
+      pieces,
+      hold,
+      goal,
+    })
+  }
+}
+
+fn parse_cell(name: &str, cell: char) -> Result<Option<Mino>, PuzzleError> {
+  match cell {
+    '.' => Ok(None),
+    'G' => Ok(Some(Mino::Garbage)),
+    _ => Tetromino::from_char(cell)
+      .map(|tetromino| Some(Mino::Piece(tetromino)))
+      .ok_or_else(|| PuzzleError::UnknownCell(name.to_string(), cell)),
+  }
+}
+
+fn parse_piece(name: &str, piece: char) -> Result<Tetromino, PuzzleError> {
+  Tetromino::from_char(piece).ok_or_else(|| PuzzleError::UnknownPiece(name.to_string(), piece))
+}
+
+/// Reads a list of puzzles in the format described in the [module documentation](self).
+pub fn parse_puzzles(text: &str) -> Result<Vec<Puzzle>, PuzzleError> {
+  let files: Vec<PuzzleFile> = serde_json::from_str(text).map_err(PuzzleError::Json)?;
+  if files.is_empty() {
+    return Err(PuzzleError::Empty);
+  }
+  files.into_iter().map(Puzzle::from_file).collect()
+}
+
+/// Why a puzzle list could not be read. Every variant but the first two names the puzzle at
+/// fault.
+#[derive(Debug)]
+pub enum PuzzleError {
+  Json(serde_json::Error),
+  Empty,
+  UnevenBoard(String),
+  NarrowBoard(String),
+  UnknownCell(String, char),
+  UnknownPiece(String, char),
+  NoPieces(String),
+}
+
+impl fmt::Display for PuzzleError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Json(error) => write!(f, "invalid puzzle list: {error}"),
+      Self::Empty => write!(f, "the puzzle list is empty"),
+      Self::UnevenBoard(name) => write!(f, "{name}: board rows must all be as wide"),
+      Self::NarrowBoard(name) => write!(f, "{name}: boards must be at least 4 cells wide"),
+      Self::UnknownCell(name, cell) => write!(f, "{name}: unknown board cell '{cell}'"),
+      Self::UnknownPiece(name, piece) => write!(f, "{name}: unknown piece '{piece}'"),
+      Self::NoPieces(name) => write!(f, "{name}: there are no pieces to play"),
+    }
+  }
+}
+
+impl Error for PuzzleError {}
+
+/// A list of puzzles and the attempt at the current one.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Puzzles {
+  puzzles: Vec<Puzzle>,
+  index: usize,
+  is_set_up: bool,
+  pieces: u32,
+  lines: u32,
+  /// Whether the puzzle was solved, once the attempt is over.
+  outcome: Option<bool>,
+  time: Duration,
+}
+
+impl Puzzles {
+  /// Starts on the first of `puzzles`, which must not be empty.
+  pub fn new(puzzles: Vec<Puzzle>) -> Self {
+    assert!(!puzzles.is_empty(), "there should be a puzzle to play");
+    Self {
+      puzzles,
+      index: 0,
+      is_set_up: false,
+      pieces: 0,
+      lines: 0,
+      outcome: None,
+      time: Duration::ZERO,
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if !self.is_set_up {
+      let puzzle = self.puzzle();
+      game.set_up_fixed(&puzzle.rows, &puzzle.pieces, puzzle.hold);
+      self.is_set_up = true;
+    }
+    if self.outcome.is_some() {
+      return false;
+    }
+
+    self.time += dt;
+    let Some(update_info) = game.update(dt, input, settings) else {
+      if game.has_lost() {
+        self.outcome = Some(false);
+      }
+      return false;
+    };
+
+    self.pieces += 1;
+    self.lines += update_info.cleared_lines;
+    if self.is_solved_by(&update_info) {
+      self.outcome = Some(true);
+    } else if game.has_lost() || self.pieces >= self.puzzle().piece_limit() {
+      self.outcome = Some(false);
+    }
+    true
+  }
+
+  fn is_solved_by(&self, update_info: &UpdateInfo) -> bool {
+    match self.puzzle().goal {
+      PuzzleGoal::PerfectClear => update_info.is_all_clear,
+      PuzzleGoal::TSpin { lines } => {
+        update_info.spin == Some((Tetromino::T, SpinType::Proper))
+          && update_info.cleared_lines == lines
+      }
+      PuzzleGoal::Lines { lines, .. } => self.lines >= lines,
+    }
+  }
+
+  pub fn puzzle(&self) -> &Puzzle {
+    &self.puzzles[self.index]
+  }
+
+  /// The position of the current puzzle in the list, from 0, and the length of the list.
+  pub fn progress(&self) -> (usize, usize) {
+    (self.index, self.puzzles.len())
+  }
+
+  /// Moves on to the next puzzle, or back to the first one after the last.
+  pub fn next_puzzle(&mut self) {
+    self.index = (self.index + 1) % self.puzzles.len();
+    self.restart();
+  }
+
+  pub fn prev_puzzle(&mut self) {
+    self.index = (self.index + self.puzzles.len() - 1) % self.puzzles.len();
+    self.restart();
+  }
+
+  /// Clears the attempt. The board is set up again on the next update.
+  pub fn restart(&mut self) {
+    self.is_set_up = false;
+    self.pieces = 0;
+    self.lines = 0;
+    self.outcome = None;
+    self.time = Duration::ZERO;
+  }
+
+  /// The pieces placed so far.
+  pub fn pieces(&self) -> u32 {
+    self.pieces
+  }
+
+  pub fn time(&self) -> Duration {
+    self.time
+  }
+
+  /// Whether the puzzle was solved, once the attempt is over.
+  pub fn outcome(&self) -> Option<bool> {
+    self.outcome
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.outcome.is_some()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::input::Action;
+
+  fn puzzle(pieces: &str, hold: Option<char>) -> Puzzles {
+    let hold = hold.map_or("null".to_string(), |hold| format!("\"{hold}\""));
+    let text = format!(
+      r#"[{{ "name": "Test", "board": ["GGGG.GGGGG"], "pieces": "{pieces}", "hold": {hold},
+           "goal": {{ "Lines": {{ "lines": 4, "pieces": 4 }} }} }}]"#
+    );
+    Puzzles::new(parse_puzzles(&text).expect("the test puzzle should be valid"))
+  }
+
+  fn press(puzzles: &mut Puzzles, game: &mut Game, action: Action) {
+    let mut input = Input::default();
+    input.press(action);
+    puzzles.update(game, Duration::ZERO, input, &HandlingSettings::default());
+  }
+
+  #[test]
+  fn only_the_puzzle_pieces_are_dealt() {
+    let mut puzzles = puzzle("TI", None);
+    let mut game = Game::new(0, Default::default());
+    // The first update sets the puzzle up
+    press(&mut puzzles, &mut game, Action::SoftDrop);
+    assert!(game.playfield().next_queue().queue().eq([Tetromino::I]));
+
+    press(&mut puzzles, &mut game, Action::Swap);
+    assert_eq!(game.playfield().falling_piece().tetromino, Tetromino::I);
+    assert!(game.playfield().next_queue().queue().next().is_none());
+
+    // Nothing is left to swap the I with
+    press(&mut puzzles, &mut game, Action::Swap);
+    assert_eq!(game.playfield().falling_piece().tetromino, Tetromino::I);
+    press(&mut puzzles, &mut game, Action::HardDrop);
+    assert_eq!(game.playfield().falling_piece().tetromino, Tetromino::T);
+    press(&mut puzzles, &mut game, Action::HardDrop);
+    assert!(game.has_lost());
+    assert_eq!(puzzles.outcome(), Some(false));
+  }
+
+  #[test]
+  fn the_held_piece_comes_last() {
+    let mut puzzles = puzzle("T", Some('O'));
+    let mut game = Game::new(0, Default::default());
+    press(&mut puzzles, &mut game, Action::HardDrop);
+    assert!(!game.has_lost());
+    assert_eq!(game.playfield().falling_piece().tetromino, Tetromino::O);
+    assert_eq!(game.playfield().holding_piece(), None);
+  }
+}
@@ -0,0 +1,122 @@
+//! Clearing a set number of lines as fast as possible.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{HandlingSettings, game::Game, input::Input};
+
+/// The lines between two split times.
+pub const SPLIT_LINES: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum SprintGoal {
+  Twenty,
+  #[default]
+  Forty,
+  Hundred,
+}
+
+impl SprintGoal {
+  pub fn lines(self) -> u32 {
+    match self {
+      Self::Twenty => 20,
+      Self::Forty => 40,
+      Self::Hundred => 100,
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Twenty => "20 Lines",
+      Self::Forty => "40 Lines",
+      Self::Hundred => "100 Lines",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::Twenty => Self::Forty,
+      Self::Forty => Self::Hundred,
+      Self::Hundred => Self::Twenty,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::Twenty => Self::Hundred,
+      Self::Forty => Self::Twenty,
+      Self::Hundred => Self::Forty,
+    }
+  }
+}
+
+/// The clock and splits of a sprint. Nothing moves until the first input, which also starts
+/// the clock.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Sprint {
+  goal: SprintGoal,
+  time: Option<Duration>,
+  splits: Vec<Duration>,
+  is_finished: bool,
+}
+
+impl Sprint {
+  pub fn new(goal: SprintGoal) -> Self {
+    Self {
+      goal,
+      time: None,
+      splits: Vec::new(),
+      is_finished: false,
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if self.is_finished || game.has_lost() {
+      return false;
+    }
+
+    let (dt, time) = match self.time {
+      Some(time) => (dt, time + dt),
+      None if input.is_empty() => return false,
+      None => (Duration::ZERO, Duration::ZERO),
+    };
+    self.time = Some(time);
+
+    let locked = game.update(dt, input, settings).is_some();
+    if locked {
+      let lines = game.lines().min(self.goal.lines());
+      while (self.splits.len() as u32) < lines / SPLIT_LINES {
+        self.splits.push(time);
+      }
+      self.is_finished = lines == self.goal.lines();
+    }
+    locked
+  }
+
+  pub fn goal(&self) -> SprintGoal {
+    self.goal
+  }
+
+  pub fn has_started(&self) -> bool {
+    self.time.is_some()
+  }
+
+  pub fn time(&self) -> Duration {
+    self.time.unwrap_or_default()
+  }
+
+  /// The time at which every [`SPLIT_LINES`] lines were reached.
+  pub fn splits(&self) -> &[Duration] {
+    &self.splits
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.is_finished
+  }
+}
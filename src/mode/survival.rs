@@ -0,0 +1,137 @@
+//! Lasting as long as possible while garbage keeps rising from below.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{HandlingSettings, game::Game, garbage::GarbageQueue, input::Input};
+
+/// The time between rising rows at the start.
+pub const FIRST_RISE_INTERVAL: Duration = Duration::from_secs(4);
+/// The time between rising rows never gets shorter than this.
+pub const LAST_RISE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The clock of a survival run and the garbage rising on it. Every row gets a hole of its own, and
+/// like a sprint the clock starts with the first input.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Survival {
+  /// Created along with the first row, seeded from the game so the same seed rises the same
+  /// garbage.
+  garbage: Option<GarbageQueue>,
+  time: Option<Duration>,
+  next_rise: Duration,
+  is_over: bool,
+}
+
+impl Survival {
+  pub fn new() -> Self {
+    Self {
+      garbage: None,
+      time: None,
+      next_rise: FIRST_RISE_INTERVAL,
+      is_over: false,
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if self.is_over {
+      return false;
+    }
+
+    let (dt, time) = match self.time {
+      Some(time) => (dt, time + dt),
+      None if input.is_empty() => return false,
+      None => (Duration::ZERO, Duration::ZERO),
+    };
+    self.time = Some(time);
+
+    let locked = game.update(dt, input, settings).is_some();
+    while time >= self.next_rise && !game.has_lost() {
+      self.rise(game);
+      self.next_rise += rise_interval(self.next_rise);
+    }
+    self.is_over = game.has_lost();
+    locked
+  }
+
+  /// Pushes a garbage row up from the bottom of the board, with a hole rolled for it alone.
+  fn rise(&mut self, game: &mut Game) {
+    let garbage = self.garbage.get_or_insert_with(|| {
+      let playfield = game.playfield();
+      GarbageQueue::new(
+        game.seed(),
+        playfield.size().width as usize,
+        playfield.rules().garbage_messiness,
+      )
+    });
+
+    garbage.push(1);
+    game.add_garbage(&garbage.take(1));
+  }
+
+  pub fn has_started(&self) -> bool {
+    self.time.is_some()
+  }
+
+  /// How long the run has lasted.
+  pub fn time(&self) -> Duration {
+    self.time.unwrap_or_default()
+  }
+
+  /// The time left before the next row rises.
+  pub fn time_to_rise(&self) -> Duration {
+    self.next_rise.saturating_sub(self.time())
+  }
+
+  /// Whether the board topped out, which is how every run ends.
+  pub fn is_finished(&self) -> bool {
+    self.is_over
+  }
+}
+
+impl Default for Survival {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// The time between rising rows after surviving for `time`. It shrinks by a tenth every 30
+/// seconds, down to [`LAST_RISE_INTERVAL`].
+pub fn rise_interval(time: Duration) -> Duration {
+  const SPEED_UP_TIME: Duration = Duration::from_secs(30);
+  let speed_ups = time.as_secs_f64() / SPEED_UP_TIME.as_secs_f64();
+  FIRST_RISE_INTERVAL
+    .mul_f64(0.9f64.powf(speed_ups))
+    .max(LAST_RISE_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rules::Rules;
+
+  #[test]
+  fn rows_get_their_own_holes() {
+    let rules = Rules {
+      garbage_messiness: 0,
+      ..Rules::default()
+    };
+    let mut game = Game::new(0, rules);
+    let mut survival = Survival::new();
+    for _ in 0..10 {
+      survival.rise(&mut game);
+    }
+
+    let holes: Vec<_> = game.playfield().grid()[..10]
+      .iter()
+      .map(|row| row.iter().position(Option::is_none))
+      .collect();
+    assert!(holes.iter().all(Option::is_some));
+    assert!(holes.iter().any(|&hole| hole != holes[0]));
+  }
+}
@@ -0,0 +1,145 @@
+//! Scoring as much as possible before the time runs out.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{HandlingSettings, game::Game, input::Input};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum UltraLimit {
+  OneMinute,
+  #[default]
+  TwoMinutes,
+  ThreeMinutes,
+  FiveMinutes,
+}
+
+impl UltraLimit {
+  pub fn duration(self) -> Duration {
+    let minutes = match self {
+      Self::OneMinute => 1,
+      Self::TwoMinutes => 2,
+      Self::ThreeMinutes => 3,
+      Self::FiveMinutes => 5,
+    };
+    Duration::from_secs(60 * minutes)
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::OneMinute => "1 Minute",
+      Self::TwoMinutes => "2 Minutes",
+      Self::ThreeMinutes => "3 Minutes",
+      Self::FiveMinutes => "5 Minutes",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::OneMinute => Self::TwoMinutes,
+      Self::TwoMinutes => Self::ThreeMinutes,
+      Self::ThreeMinutes => Self::FiveMinutes,
+      Self::FiveMinutes => Self::OneMinute,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::OneMinute => Self::FiveMinutes,
+      Self::TwoMinutes => Self::OneMinute,
+      Self::ThreeMinutes => Self::TwoMinutes,
+      Self::FiveMinutes => Self::ThreeMinutes,
+    }
+  }
+}
+
+/// How many times a kind of clear was made and the points it earned, combo and back-to-back
+/// bonuses included.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ClearTally {
+  pub name: String,
+  pub count: u32,
+  pub score: u64,
+}
+
+/// The countdown of a score attack. Like a sprint, the clock starts with the first input, and the
+/// board freezes once it reaches zero.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ultra {
+  limit: UltraLimit,
+  time: Option<Duration>,
+  clears: Vec<ClearTally>,
+}
+
+impl Ultra {
+  pub fn new(limit: UltraLimit) -> Self {
+    Self {
+      limit,
+      time: None,
+      clears: Vec::new(),
+    }
+  }
+
+  pub fn update(
+    &mut self,
+    game: &mut Game,
+    dt: Duration,
+    input: Input,
+    settings: &HandlingSettings,
+  ) -> bool {
+    if self.is_finished() || game.has_lost() {
+      return false;
+    }
+
+    let dt = match self.time {
+      Some(time) => dt.min(self.limit.duration() - time),
+      None if input.is_empty() => return false,
+      None => Duration::ZERO,
+    };
+    self.time = Some(self.time.unwrap_or_default() + dt);
+
+    let score = game.score();
+    let locked = game.update(dt, input, settings).is_some();
+    if locked && let Some(name) = game.message().clear_name() {
+      self.tally(name, game.score() - score);
+    }
+    locked
+  }
+
+  fn tally(&mut self, name: String, score: u64) {
+    match self.clears.iter_mut().find(|tally| tally.name == name) {
+      Some(tally) => {
+        tally.count += 1;
+        tally.score += score;
+      }
+      None => self.clears.push(ClearTally {
+        name,
+        count: 1,
+        score,
+      }),
+    }
+  }
+
+  pub fn limit(&self) -> UltraLimit {
+    self.limit
+  }
+
+  pub fn has_started(&self) -> bool {
+    self.time.is_some()
+  }
+
+  pub fn remaining_time(&self) -> Duration {
+    self.limit.duration() - self.time.unwrap_or_default()
+  }
+
+  /// Every kind of clear made so far, from the highest scoring one down.
+  pub fn clears(&self) -> Vec<ClearTally> {
+    let mut clears = self.clears.clone();
+    clears.sort_by_key(|tally| std::cmp::Reverse(tally.score));
+    clears
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.remaining_time().is_zero()
+  }
+}
@@ -0,0 +1,922 @@
+//! The board, the falling piece and everything that happens to them every frame.
+
+pub mod falling_piece;
+pub mod finesse;
+pub mod next_queue;
+pub mod randomizer;
+pub mod rotation_system;
+pub mod tetromino;
+
+use serde::{Deserialize, Serialize};
+use std::{iter, time::Duration};
+
+use crate::{
+  HandlingSettings,
+  input::{Action, Input},
+  line_clear_message::SpinType,
+  playfield::{
+    falling_piece::{FallingPiece, Orientation, RotationType, Shift},
+    finesse::Finesse,
+    next_queue::NextQueue,
+    tetromino::Tetromino,
+  },
+  rules::{Rules, SpinDetection, Style},
+};
+
+/// Rows above the visible ones, where pieces spawn and can still be stacked for a while.
+const BUFFER_HEIGHT: i32 = 20;
+
+/// The dimensions of a board.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct BoardSize {
+  pub width: i32,
+  /// Rows drawn on screen. Pieces spawn right above them, so anything locked entirely above this
+  /// line tops the player out.
+  pub visible_height: i32,
+}
+
+impl Default for BoardSize {
+  fn default() -> Self {
+    Self {
+      width: 10,
+      visible_height: 20,
+    }
+  }
+}
+
+impl BoardSize {
+  pub fn height(self) -> i32 {
+    self.visible_height + BUFFER_HEIGHT
+  }
+
+  /// Where the rotation center of new pieces goes.
+  fn spawn_position(self) -> (i8, i8) {
+    ((self.width as i8 - 1) / 2, self.visible_height as i8)
+  }
+}
+
+/// What fills a cell of the grid.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum Mino {
+  Piece(Tetromino),
+  Garbage,
+}
+
+/// The locked minos, indexed as `grid[y][x]` with `y = 0` being the bottom row.
+pub type Grid = Vec<Vec<Option<Mino>>>;
+
+/// A board with the pieces to play on it, in the form [`Playfield::set_up`] takes.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Position {
+  /// From the bottom row up, without the empty rows above the stack.
+  pub rows: Grid,
+  /// The falling piece first, then the queue.
+  pub pieces: Vec<Tetromino>,
+  pub hold: Option<Tetromino>,
+}
+
+/// What happened when a piece locked.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UpdateInfo {
+  pub cleared_lines: u32,
+  pub spin: Option<(Tetromino, SpinType)>,
+  pub is_all_clear: bool,
+  /// How many keys the piece took, when it went straight down to where it locked.
+  pub finesse: Option<Finesse>,
+}
+
+/// A single board with its queue, hold slot and falling piece.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Playfield {
+  grid: Grid,
+  next_queue: NextQueue,
+  falling_piece: FallingPiece,
+  holding_piece: Option<Tetromino>,
+  can_swap: bool,
+  /// The rotation and kick index of the last successful move, if that move was a rotation.
+  last_rotation: Option<(RotationType, usize)>,
+  last_drop: Duration,
+  lock_delay: Duration,
+  lock_delay_resets: u32,
+  /// The time left before the falling piece enters, during which only DAS charges.
+  entry_delay: Duration,
+  das_press: Option<(Shift, Duration)>,
+  /// The time since the last auto repeated shift, once DAS has charged.
+  #[serde(default)]
+  arr_charge: Duration,
+  /// The shifts and rotations pressed since the falling piece entered.
+  #[serde(default)]
+  piece_keys: u32,
+  has_lost: bool,
+  rules: Rules,
+}
+
+impl Playfield {
+  /// Creates an empty board whose pieces are drawn from a queue seeded with `seed`.
+  pub fn new(seed: u64, rules: Rules) -> Self {
+    let mut next_queue = NextQueue::new(seed, rules.randomizer);
+    let first = next_queue
+      .next_tetromino()
+      .expect("new queues deal from the randomizer");
+    let falling_piece = spawn_tetromino(first, &rules);
+    Self {
+      grid: vec![vec![None; rules.board.width as usize]; rules.board.height() as usize],
+      next_queue,
+      falling_piece,
+      holding_piece: None,
+      can_swap: true,
+      last_rotation: None,
+      last_drop: Duration::ZERO,
+      lock_delay: Duration::ZERO,
+      lock_delay_resets: 0,
+      entry_delay: Duration::ZERO,
+      das_press: None,
+      arr_charge: Duration::ZERO,
+      piece_keys: 0,
+      has_lost: false,
+      rules,
+    }
+  }
+
+  pub fn has_lost(&self) -> bool {
+    self.has_lost
+  }
+
+  /// The board as it is now, with the falling piece back at the top of the queue.
+  pub fn position(&self) -> Position {
+    let height = self
+      .grid
+      .iter()
+      .rposition(|row| row.iter().any(Option::is_some))
+      .map_or(0, |top| top + 1);
+    Position {
+      rows: self.grid[..height].to_vec(),
+      pieces: iter::once(self.falling_piece.tetromino)
+        .chain(self.next_queue.queue())
+        .collect(),
+      hold: self.holding_piece,
+    }
+  }
+
+  pub fn rules(&self) -> &Rules {
+    &self.rules
+  }
+
+  pub fn size(&self) -> BoardSize {
+    self.rules.board
+  }
+
+  pub fn grid(&self) -> &Grid {
+    &self.grid
+  }
+
+  /// Whether the falling piece is still waiting to enter after the last lock.
+  pub fn is_in_entry_delay(&self) -> bool {
+    !self.entry_delay.is_zero()
+  }
+
+  pub fn falling_piece(&self) -> &FallingPiece {
+    &self.falling_piece
+  }
+
+  /// Where the falling piece would land if it were hard dropped now.
+  pub fn ghost_piece(&self) -> FallingPiece {
+    let mut ghost_piece = self.falling_piece.clone();
+    ghost_piece.fall();
+    while valid_position(&self.grid, &ghost_piece) {
+      ghost_piece.fall();
+    }
+    ghost_piece.unfall();
+    ghost_piece
+  }
+
+  pub fn holding_piece(&self) -> Option<Tetromino> {
+    self.holding_piece
+  }
+
+  pub fn can_swap(&self) -> bool {
+    self.can_swap
+  }
+
+  pub fn next_queue(&self) -> &NextQueue {
+    &self.next_queue
+  }
+
+  /// The next piece as it will appear once spawned, used to warn about an imminent top out.
+  pub fn next_spawn(&self) -> Option<FallingPiece> {
+    let tetromino = self.next_queue.peek()?;
+    Some(spawn_tetromino(tetromino, &self.rules))
+  }
+
+  /// Whether the area around the spawn position is filled enough to risk a top out.
+  pub fn is_in_danger(&self) -> bool {
+    let (spawn_x, spawn_y) = self.size().spawn_position();
+    let x_danger = (spawn_x as i32 - 2).max(0)..(spawn_x as i32 + 2).min(self.size().width);
+    let y_danger = (spawn_y as i32 - 5).max(0)..spawn_y as i32;
+
+    let mut danger_zone = x_danger.flat_map(|x| y_danger.clone().map(move |y| (x, y)));
+    danger_zone.any(|(x, y)| self.grid[y as usize][x as usize].is_some())
+  }
+
+  /// The number of rows that still hold garbage.
+  pub fn garbage_rows(&self) -> usize {
+    self
+      .grid
+      .iter()
+      .filter(|row| row.contains(&Some(Mino::Garbage)))
+      .count()
+  }
+
+  /// Pushes the stack up by one row per hole, from the top row down, filling every new row but its
+  /// hole. The falling piece is pushed up along if the rows would overlap it.
+  pub fn add_garbage(&mut self, holes: &[usize]) {
+    for &hole in holes {
+      let top_row = self.grid.pop().expect("boards should have rows");
+      if top_row.iter().any(Option::is_some) {
+        self.has_lost = true;
+      }
+
+      let mut row = vec![Some(Mino::Garbage); self.size().width as usize];
+      row[hole] = None;
+      self.grid.insert(0, row);
+    }
+
+    for _ in holes {
+      if valid_position(&self.grid, &self.falling_piece) {
+        break;
+      }
+      self.falling_piece.translate((0, 1));
+    }
+    if !valid_position(&self.grid, &self.falling_piece) {
+      self.has_lost = true;
+    }
+  }
+
+  /// Replaces the board with `rows`, from the bottom one up, and deals `pieces` first, with `hold`
+  /// in the hold slot. The first of `pieces` becomes the falling piece. Every row must be as wide
+  /// as the board.
+  pub fn set_up(
+    &mut self,
+    rows: &[Vec<Option<Mino>>],
+    pieces: &[Tetromino],
+    hold: Option<Tetromino>,
+  ) {
+    self.next_queue.preset(pieces);
+    self.set_up_board(rows, hold);
+  }
+
+  /// Sets the board up as [`Playfield::set_up`] does, but deals nothing past `pieces`, which must
+  /// not be empty. Running out of pieces ends the game like a top out.
+  pub fn set_up_fixed(
+    &mut self,
+    rows: &[Vec<Option<Mino>>],
+    pieces: &[Tetromino],
+    hold: Option<Tetromino>,
+  ) {
+    self.next_queue.preset_fixed(pieces);
+    self.set_up_board(rows, hold);
+  }
+
+  /// Puts `rows` and `hold` in place and brings in the first piece of the queue.
+  fn set_up_board(&mut self, rows: &[Vec<Option<Mino>>], hold: Option<Tetromino>) {
+    let size = self.size();
+    self.grid = vec![vec![None; size.width as usize]; size.height() as usize];
+    for (row, set_up_row) in self.grid.iter_mut().zip(rows) {
+      row.copy_from_slice(set_up_row);
+    }
+
+    let first = self
+      .next_queue
+      .next_tetromino()
+      .expect("set ups should have a piece to play");
+    self.falling_piece = spawn_tetromino(first, &self.rules);
+    self.holding_piece = hold;
+    self.can_swap = true;
+    self.last_rotation = None;
+    self.last_drop = Duration::ZERO;
+    self.lock_delay = Duration::ZERO;
+    self.lock_delay_resets = 0;
+    self.entry_delay = Duration::ZERO;
+    self.piece_keys = 0;
+    self.has_lost = false;
+  }
+
+  /// Advances the board by `dt` with this frame's `input`, returning what happened if a piece
+  /// locked.
+  pub fn update(&mut self, input: Input, h: &HandlingSettings, dt: Duration) -> Option<UpdateInfo> {
+    if self.has_lost {
+      return None;
+    }
+
+    if !self.entry_delay.is_zero() {
+      self.entry_delay = self.entry_delay.saturating_sub(dt);
+      self.charge_das(input, dt);
+      return None;
+    }
+
+    self.last_drop += dt;
+    self.lock_delay += dt;
+
+    let input = match self.rules.style {
+      Style::Guideline | Style::Master => input,
+      Style::Classic => input
+        .without(Action::Swap)
+        .without(Action::HardDrop)
+        .without(Action::OneEighty),
+    };
+    self.handle_swap(input);
+    self.piece_keys += finesse::keys(input);
+    self.handle_shifts(input, h, dt);
+    self.handle_rotations(input);
+    let update_info = self.handle_drops(input, h)?;
+
+    self.entry_delay = h.entry_delay;
+    if update_info.cleared_lines > 0 {
+      self.entry_delay += h.line_clear_delay;
+    }
+    Some(update_info)
+  }
+
+  /// Keeps track of how long a shift is held while pieces cannot move, so that it can take effect
+  /// as soon as the next one enters.
+  fn charge_das(&mut self, input: Input, dt: Duration) {
+    let shift = if input.is_held(Action::Left) {
+      Shift::Left
+    } else if input.is_held(Action::Right) {
+      Shift::Right
+    } else {
+      self.das_press = None;
+      return;
+    };
+
+    let duration = self
+      .das_press
+      .filter(|&(s_shift, _)| s_shift == shift)
+      .map(|(_, duration)| duration)
+      .unwrap_or_default();
+    self.das_press = Some((shift, duration + dt));
+  }
+
+  fn handle_swap(&mut self, input: Input) {
+    if !input.is_pressed(Action::Swap) || !self.can_swap {
+      return;
+    }
+
+    // A fixed sequence that ran out leaves nothing to swap with an empty hold slot
+    let Some(swapped) = self
+      .holding_piece
+      .or_else(|| self.next_queue.next_tetromino())
+    else {
+      return;
+    };
+    let current_tetromino = self.falling_piece.tetromino;
+    self.falling_piece = spawn_tetromino(swapped, &self.rules);
+    self.holding_piece = Some(current_tetromino);
+    self.can_swap = false;
+    self.last_drop = Duration::ZERO;
+    self.lock_delay = Duration::ZERO;
+    self.lock_delay_resets = 0;
+    self.last_rotation = None;
+    self.piece_keys = 0;
+  }
+
+  fn handle_shifts(&mut self, input: Input, h: &HandlingSettings, dt: Duration) {
+    let mut try_shifting = |shift| {
+      let mut shifted_piece = self.falling_piece.clone();
+      shifted_piece.shift(shift);
+      if valid_position(&self.grid, &shifted_piece) {
+        self.falling_piece = shifted_piece;
+        self.lock_delay = Duration::ZERO;
+        self.lock_delay_resets += 1;
+        self.last_rotation = None;
+      }
+    };
+    if input.is_pressed(Action::Left) {
+      try_shifting(Shift::Left);
+    } else if input.is_pressed(Action::Right) {
+      try_shifting(Shift::Right);
+    }
+
+    let mut try_das = |shift| {
+      let charged = self
+        .das_press
+        .filter(|&(s_shift, _)| s_shift == shift)
+        .map(|(_, duration)| duration)
+        .unwrap_or_default();
+      let duration = charged + dt;
+
+      self.das_press = Some((shift, duration));
+
+      if duration < h.das {
+        return;
+      }
+
+      // The first auto shift comes as DAS charges and the next ones every ARR after it
+      let shifts = if h.arr.is_zero() {
+        u32::MAX
+      } else {
+        let first_shift = if charged < h.das {
+          self.arr_charge = duration - h.das;
+          1
+        } else {
+          self.arr_charge += dt;
+          0
+        };
+        let repeats = (self.arr_charge.as_nanos() / h.arr.as_nanos()) as u32;
+        self.arr_charge -= h.arr * repeats;
+        first_shift + repeats
+      };
+
+      let mut shifted_piece = self.falling_piece.clone();
+      shifted_piece.shift(shift);
+      for _ in 0..shifts {
+        if !valid_position(&self.grid, &shifted_piece) {
+          break;
+        }
+        self.falling_piece = shifted_piece.clone();
+        self.lock_delay = Duration::ZERO;
+        self.lock_delay_resets += 1;
+        self.last_rotation = None;
+        shifted_piece.shift(shift);
+      }
+    };
+
+    if input.is_held(Action::Left) {
+      try_das(Shift::Left);
+    } else if input.is_held(Action::Right) {
+      try_das(Shift::Right);
+    } else {
+      self.das_press = None;
+    }
+  }
+
+  fn handle_rotations(&mut self, input: Input) {
+    let rotation_type = if input.is_pressed(Action::Clockwise) {
+      RotationType::Clockwise
+    } else if input.is_pressed(Action::CounterClockwise) {
+      RotationType::CounterClockwise
+    } else if input.is_pressed(Action::OneEighty) {
+      RotationType::OneEighty
+    } else {
+      return;
+    };
+
+    let rotation_system = self.rules.rotation_system.rotation_system();
+    let is_blocked = |x, y| is_blocked(&self.grid, x, y);
+    let Some((rotated_piece, kick)) =
+      rotation_system.rotate(&self.falling_piece, rotation_type, &is_blocked)
+    else {
+      return;
+    };
+
+    self.falling_piece = rotated_piece;
+    self.lock_delay = Duration::ZERO;
+    self.lock_delay_resets += 1;
+    self.last_rotation = Some((rotation_type, kick));
+  }
+
+  fn handle_drops(&mut self, input: Input, h: &HandlingSettings) -> Option<UpdateInfo> {
+    if input.is_pressed(Action::HardDrop) {
+      let mut fallen = self.falling_piece.clone();
+      fallen.fall();
+      while valid_position(&self.grid, &fallen) {
+        self.falling_piece = fallen.clone();
+        fallen.fall();
+        self.last_rotation = None;
+      }
+      return Some(self.solidify_piece());
+    }
+
+    // At high speeds several rows are due on a single frame, and all of them when the fall time
+    // is zero.
+    let fall_time = if input.is_held(Action::SoftDrop) {
+      h.gravity.min(h.soft_drop)
+    } else {
+      h.gravity
+    };
+    let fall_rows = if fall_time.is_zero() {
+      self.last_drop = Duration::ZERO;
+      self.size().height() as u32
+    } else {
+      let rows = (self.last_drop.as_nanos() / fall_time.as_nanos()) as u32;
+      self.last_drop -= fall_time * rows;
+      rows
+    };
+
+    let mut fallen_piece = self.falling_piece.clone();
+    fallen_piece.fall();
+    let can_fall = valid_position(&self.grid, &fallen_piece);
+    let can_wait = self.lock_delay < h.lock_delay;
+    let can_reset = self.lock_delay_resets < h.lock_delay_resets;
+    if !can_fall && (!can_wait || !can_reset) {
+      return Some(self.solidify_piece());
+    }
+
+    if can_fall && fall_rows > 0 {
+      self.last_rotation = None;
+      self.falling_piece = fallen_piece;
+      for _ in 1..fall_rows {
+        let mut fallen_piece = self.falling_piece.clone();
+        fallen_piece.fall();
+        if !valid_position(&self.grid, &fallen_piece) {
+          break;
+        }
+        self.falling_piece = fallen_piece;
+      }
+      self.lock_delay = Duration::ZERO;
+      self.lock_delay_resets = 0;
+    }
+
+    None
+  }
+
+  /// Whether the falling piece, before locking, counts as a spin under the current rules.
+  fn is_spin(&self) -> Option<SpinType> {
+    let piece = &self.falling_piece;
+    match piece.tetromino {
+      Tetromino::T => return self.is_t_spin(),
+      Tetromino::O => return None,
+      _ => {}
+    }
+
+    let spin_type = match self.rules.spin_detection {
+      SpinDetection::TSpins => return None,
+      SpinDetection::AllMini => SpinType::Mini,
+      SpinDetection::AllSpin => SpinType::Proper,
+    };
+
+    let is_immobile = [(-1, 0), (1, 0), (0, 1)].into_iter().all(|offset| {
+      let mut moved_piece = piece.clone();
+      moved_piece.translate(offset);
+      !valid_position(&self.grid, &moved_piece)
+    });
+    is_immobile.then_some(spin_type)
+  }
+
+  /// The three-corner rule: a spin when three corners around the center are filled, and a mini
+  /// unless both corners the T points towards are, or the rotation needed the TST kick.
+  fn is_t_spin(&self) -> Option<SpinType> {
+    // The fifth SRS test, which moves the piece two rows down on a quarter turn.
+    const TST_KICK: usize = 4;
+
+    let (piece, grid) = (&self.falling_piece, &self.grid);
+    let mut corners = [(-1, 1), (1, 1), (1, -1), (-1, -1)];
+    corners.rotate_left(match piece.orientation {
+      Orientation::Up => 0,
+      Orientation::Right => 1,
+      Orientation::Down => 2,
+      Orientation::Left => 3,
+    });
+
+    let front_count = corners[0..2]
+      .iter()
+      .filter(|&&(cx, cy)| {
+        let x = piece.x as i32 + cx;
+        let y = piece.y as i32 + cy;
+        is_blocked(grid, x, y)
+      })
+      .count();
+    let back_count = corners[2..4]
+      .iter()
+      .filter(|&&(cx, cy)| {
+        let x = piece.x as i32 + cx;
+        let y = piece.y as i32 + cy;
+        is_blocked(grid, x, y)
+      })
+      .count();
+
+    let is_tst_kick = matches!(
+      self.last_rotation,
+      Some((
+        RotationType::Clockwise | RotationType::CounterClockwise,
+        TST_KICK
+      ))
+    );
+
+    if front_count + back_count < 3 {
+      None
+    } else if front_count == 2 || is_tst_kick {
+      Some(SpinType::Proper)
+    } else {
+      Some(SpinType::Mini)
+    }
+  }
+
+  /// The keys the falling piece took against the fewest that put it where it is, if it got there
+  /// without tucks or spins.
+  fn finesse(&self) -> Option<Finesse> {
+    let spawned = spawn_tetromino(self.falling_piece.tetromino, &self.rules);
+    let minimum = finesse::minimum_keys(
+      &self.grid,
+      &spawned,
+      &self.falling_piece,
+      self.rules.rotation_system.rotation_system(),
+      self.rules.style != Style::Classic,
+    )?;
+    Some(Finesse {
+      keys: self.piece_keys,
+      minimum,
+    })
+  }
+
+  fn solidify_piece(&mut self) -> UpdateInfo {
+    let tetromino = self.falling_piece.tetromino;
+    let spin_type = self.last_rotation.and_then(|_| self.is_spin());
+    let finesse = self.finesse();
+
+    let mut topped_out = true;
+
+    for (cx, cy) in self.falling_piece.map {
+      let x = cx as i32 + self.falling_piece.x as i32;
+      let y = cy as i32 + self.falling_piece.y as i32;
+      self.grid[y as usize][x as usize] = Some(Mino::Piece(self.falling_piece.tetromino));
+
+      if y < self.size().visible_height {
+        topped_out = false;
+      }
+    }
+
+    let mut cleared_lines = 0;
+    for row_idx in (0..self.grid.len()).rev() {
+      if self.grid[row_idx].iter().all(|&m| m.is_some()) {
+        self.grid.remove(row_idx);
+        self.grid.push(vec![None; self.size().width as usize]);
+        cleared_lines += 1;
+      }
+    }
+
+    let is_all_clear = self.grid.iter().flatten().all(|mino| mino.is_none());
+
+    // Once a fixed sequence runs out, the piece in the hold slot is the last one left
+    let next_tetromino = self
+      .next_queue
+      .next_tetromino()
+      .or_else(|| self.holding_piece.take());
+    let ran_out = next_tetromino.is_none();
+    if let Some(next_tetromino) = next_tetromino {
+      self.falling_piece = spawn_tetromino(next_tetromino, &self.rules);
+    }
+    self.last_drop = Duration::ZERO;
+    self.lock_delay = Duration::ZERO;
+    self.lock_delay_resets = 0;
+    self.last_rotation = None;
+    self.piece_keys = 0;
+    self.can_swap = true;
+
+    let can_spawn_piece = self.falling_piece.map.iter().all(|&(cx, cy)| {
+      let x = cx as i32 + self.falling_piece.x as i32;
+      let y = cy as i32 + self.falling_piece.y as i32;
+      self.grid[y as usize][x as usize].is_none()
+    });
+
+    self.has_lost = topped_out || ran_out || !can_spawn_piece;
+    let spin = spin_type.map(|spin_type| (tetromino, spin_type));
+
+    UpdateInfo {
+      cleared_lines,
+      spin,
+      is_all_clear,
+      finesse,
+    }
+  }
+}
+
+fn spawn_tetromino(tetromino: Tetromino, rules: &Rules) -> FallingPiece {
+  let rotation_system = rules.rotation_system.rotation_system();
+  let (x, y) = rules.board.spawn_position();
+  rotation_system.spawn(tetromino, x, y)
+}
+
+fn valid_position(grid: &Grid, piece: &FallingPiece) -> bool {
+  piece.map.iter().all(|(cx, cy)| {
+    let x = (cx + piece.x) as i32;
+    let y = (cy + piece.y) as i32;
+    !is_blocked(grid, x, y)
+  })
+}
+
+/// Whether `(x, y)` is outside the board or already filled.
+fn is_blocked(grid: &Grid, x: i32, y: i32) -> bool {
+  let Some(row) = usize::try_from(y).ok().and_then(|y| grid.get(y)) else {
+    return true;
+  };
+  usize::try_from(x)
+    .ok()
+    .and_then(|x| row.get(x))
+    .is_none_or(Option::is_some)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::replay::TICK;
+
+  /// Builds a board from rows drawn top to bottom, `#` being a filled cell, with a T piece of the
+  /// given orientation centered on `(x, y)`.
+  fn setup(rows: &[&str], orientation: Orientation, x: i8, y: i8) -> Playfield {
+    let mut playfield = Playfield::new(0, Rules::default());
+    for (j, row) in rows.iter().rev().enumerate() {
+      for (i, cell) in row.chars().enumerate() {
+        playfield.grid[j][i] = (cell == '#').then_some(Mino::Garbage);
+      }
+    }
+
+    let mut piece = FallingPiece::new(Tetromino::T, x, y);
+    piece.rotate(match orientation {
+      Orientation::Up => return with_piece(playfield, piece),
+      Orientation::Right => RotationType::Clockwise,
+      Orientation::Down => RotationType::OneEighty,
+      Orientation::Left => RotationType::CounterClockwise,
+    });
+    with_piece(playfield, piece)
+  }
+
+  fn with_piece(mut playfield: Playfield, piece: FallingPiece) -> Playfield {
+    assert!(valid_position(&playfield.grid, &piece));
+    playfield.falling_piece = piece;
+    playfield
+  }
+
+  /// Rotates the falling piece and hard drops it on the same frame.
+  fn spin(playfield: &mut Playfield, rotation: Action) -> UpdateInfo {
+    let mut input = Input::default();
+    input.press(rotation);
+    input.press(Action::HardDrop);
+    playfield
+      .update(input, &HandlingSettings::default(), Duration::ZERO)
+      .expect("a hard drop always locks the piece")
+  }
+
+  #[test]
+  fn das_sliding_after_a_rotation_is_no_spin() {
+    #[rustfmt::skip]
+    let mut playfield = setup(
+      &[
+        "#.........",
+        "..........",
+        "#####.####",
+      ],
+      Orientation::Up,
+      5,
+      1,
+    );
+    let h = HandlingSettings {
+      das: Duration::ZERO,
+      ..HandlingSettings::default()
+    };
+
+    // Turning back and forth leaves the T where it was, but with a rotation as its last move
+    for rotation in [Action::Clockwise, Action::CounterClockwise] {
+      let mut input = Input::default();
+      input.press(rotation);
+      assert!(playfield.update(input, &h, Duration::ZERO).is_none());
+    }
+    assert_eq!(playfield.falling_piece.x, 5);
+
+    // Sliding under the overhang finds three corners filled, but only through a shift
+    let mut input = Input::default();
+    input.hold(Action::Left);
+    input.press(Action::HardDrop);
+    let info = playfield
+      .update(input, &h, Duration::ZERO)
+      .expect("a hard drop always locks the piece");
+    assert_eq!(playfield.grid[1][0], Some(Mino::Piece(Tetromino::T)));
+    assert_eq!(info.spin, None);
+  }
+
+  /// Holds left from the first of `frames` on, returning where the T is after each of them.
+  fn hold_left(playfield: &mut Playfield, h: &HandlingSettings, frames: &[Duration]) -> Vec<i8> {
+    let mut xs = Vec::new();
+    for (i, &dt) in frames.iter().enumerate() {
+      let mut input = Input::default();
+      if i == 0 {
+        input.press(Action::Left);
+      }
+      input.hold(Action::Left);
+      assert!(playfield.update(input, h, dt).is_none());
+      xs.push(playfield.falling_piece.x);
+    }
+    xs
+  }
+
+  fn handling(das: Duration, arr: Duration) -> HandlingSettings {
+    HandlingSettings {
+      das,
+      arr,
+      ..HandlingSettings::default()
+    }
+  }
+
+  #[test]
+  fn zero_arr_shifts_to_the_wall() {
+    let mut playfield = setup(&[], Orientation::Up, 8, 10);
+    let h = handling(Duration::from_millis(100), Duration::ZERO);
+    let frames = [0, 99, 1].map(Duration::from_millis);
+    assert_eq!(hold_left(&mut playfield, &h, &frames), [7, 7, 1]);
+  }
+
+  #[test]
+  fn arr_of_a_frame_shifts_once_a_frame() {
+    let mut playfield = setup(&[], Orientation::Up, 8, 10);
+    let h = handling(TICK * 4, TICK);
+    let mut frames = vec![TICK; 7];
+    frames[0] = Duration::ZERO;
+    assert_eq!(
+      hold_left(&mut playfield, &h, &frames),
+      [7, 7, 7, 7, 6, 5, 4]
+    );
+  }
+
+  #[test]
+  fn arr_shorter_than_a_frame_carries_over() {
+    let mut playfield = setup(&[], Orientation::Up, 8, 10);
+    let h = handling(Duration::from_millis(100), Duration::from_millis(10));
+    // DAS charges 5 ms into the first ARR, which the next frame completes twice over and the two
+    // after it only once between them
+    let frames = [0, 105, 25, 5, 5].map(Duration::from_millis);
+    assert_eq!(hold_left(&mut playfield, &h, &frames), [7, 6, 3, 3, 2]);
+  }
+
+  #[test]
+  fn tsd_is_a_full_spin() {
+    #[rustfmt::skip]
+    let mut playfield = setup(
+      &[
+        "##........",
+        "#...######",
+        "##.#######",
+      ],
+      Orientation::Right,
+      2,
+      1,
+    );
+
+    let info = spin(&mut playfield, Action::Clockwise);
+    assert_eq!(info.cleared_lines, 2);
+    assert_eq!(info.spin, Some((Tetromino::T, SpinType::Proper)));
+  }
+
+  #[test]
+  fn tst_kicks_into_a_full_spin() {
+    #[rustfmt::skip]
+    let mut playfield = setup(
+      &[
+        "..########",
+        "...#######",
+        "##.#######",
+        "#..#######",
+        "##.#######",
+      ],
+      Orientation::Up,
+      1,
+      3,
+    );
+
+    let info = spin(&mut playfield, Action::CounterClockwise);
+    assert_eq!(info.cleared_lines, 3);
+    assert_eq!(info.spin, Some((Tetromino::T, SpinType::Proper)));
+  }
+
+  #[test]
+  fn fin_is_upgraded_by_the_tst_kick() {
+    #[rustfmt::skip]
+    let mut playfield = setup(
+      &[
+        ".#########",
+        "...#######",
+        "...#######",
+        "#..#######",
+        "##.#######",
+      ],
+      Orientation::Down,
+      1,
+      3,
+    );
+
+    let info = spin(&mut playfield, Action::Clockwise);
+    assert_eq!(info.cleared_lines, 2);
+    assert_eq!(info.spin, Some((Tetromino::T, SpinType::Proper)));
+  }
+
+  #[test]
+  fn neo_stays_a_mini() {
+    #[rustfmt::skip]
+    let mut playfield = setup(
+      &[
+        ".#########",
+        "...#######",
+        "#.########",
+        "..########",
+        "..########",
+      ],
+      Orientation::Down,
+      1,
+      3,
+    );
+
+    let info = spin(&mut playfield, Action::Clockwise);
+    assert_eq!(info.cleared_lines, 2);
+    assert_eq!(info.spin, Some((Tetromino::T, SpinType::Mini)));
+  }
+}
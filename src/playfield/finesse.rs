@@ -0,0 +1,231 @@
+//! Comparing the keys a piece took to place with the fewest that place it there.
+//!
+//! Only placements reached by shifting and rotating at the spawn height and then dropping straight
+//! down are judged, so tucks and spins are never faults. Holding a shift until the piece stops
+//! counts as a single key, like a tap.
+
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::{HashSet, VecDeque},
+  time::Duration,
+};
+
+use crate::{
+  input::{Action, Input},
+  playfield::{
+    Grid,
+    falling_piece::{FallingPiece, Orientation, RotationType, Shift},
+    is_blocked,
+    rotation_system::RotationSystem,
+    valid_position,
+  },
+};
+
+/// The keys a placement took, against the fewest it could have taken.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Finesse {
+  pub keys: u32,
+  pub minimum: u32,
+}
+
+impl Finesse {
+  /// How long a fault stays on screen.
+  pub const FAULT_DURATION: Duration = Duration::from_secs(1);
+
+  pub fn is_fault(self) -> bool {
+    self.keys > self.minimum
+  }
+
+  /// The keys taken past the fewest needed.
+  pub fn extra_keys(self) -> u32 {
+    self.keys.saturating_sub(self.minimum)
+  }
+}
+
+/// The number of shifts and rotations pressed in `input`, the only keys that count.
+pub(super) fn keys(input: Input) -> u32 {
+  const KEYS: [Action; 5] = [
+    Action::Left,
+    Action::Right,
+    Action::Clockwise,
+    Action::CounterClockwise,
+    Action::OneEighty,
+  ];
+  KEYS
+    .into_iter()
+    .filter(|&key| input.is_pressed(key))
+    .count() as u32
+}
+
+/// The fewest keys taking `spawned` to the cells of `placed`, half turns included only if
+/// `one_eighty` is set. `None` if `placed` cannot be reached by dropping straight down.
+pub(super) fn minimum_keys(
+  grid: &Grid,
+  spawned: &FallingPiece,
+  placed: &FallingPiece,
+  rotation_system: &dyn RotationSystem,
+  one_eighty: bool,
+) -> Option<u32> {
+  let rotations: &[RotationType] = if one_eighty {
+    &[
+      RotationType::Clockwise,
+      RotationType::CounterClockwise,
+      RotationType::OneEighty,
+    ]
+  } else {
+    &[RotationType::Clockwise, RotationType::CounterClockwise]
+  };
+  let is_blocked = |x, y| is_blocked(grid, x, y);
+  let target = cells(placed);
+
+  let mut seen = HashSet::from([state(spawned)]);
+  let mut queue = VecDeque::from([(spawned.clone(), 0)]);
+  while let Some((piece, keys)) = queue.pop_front() {
+    if cells(&dropped(grid, &piece)) == target {
+      return Some(keys);
+    }
+
+    let shifts = [Shift::Left, Shift::Right]
+      .into_iter()
+      .flat_map(|shift| [tap(grid, &piece, shift), das(grid, &piece, shift)]);
+    let turns = rotations.iter().map(|&rotation| {
+      rotation_system
+        .rotate(&piece, rotation, &is_blocked)
+        .map(|(rotated, _)| rotated)
+    });
+    for next in shifts.chain(turns).flatten() {
+      if seen.insert(state(&next)) {
+        queue.push_back((next, keys + 1));
+      }
+    }
+  }
+  None
+}
+
+fn state(piece: &FallingPiece) -> (i8, i8, Orientation) {
+  (piece.x, piece.y, piece.orientation)
+}
+
+/// The cells `piece` covers, in an order that does not depend on its orientation.
+fn cells(piece: &FallingPiece) -> [(i8, i8); 4] {
+  let mut cells = piece.map.map(|(cx, cy)| (cx + piece.x, cy + piece.y));
+  cells.sort_unstable();
+  cells
+}
+
+fn tap(grid: &Grid, piece: &FallingPiece, shift: Shift) -> Option<FallingPiece> {
+  let mut shifted = piece.clone();
+  shifted.shift(shift);
+  valid_position(grid, &shifted).then_some(shifted)
+}
+
+fn das(grid: &Grid, piece: &FallingPiece, shift: Shift) -> Option<FallingPiece> {
+  let mut shifted = tap(grid, piece, shift)?;
+  while let Some(next) = tap(grid, &shifted, shift) {
+    shifted = next;
+  }
+  Some(shifted)
+}
+
+fn dropped(grid: &Grid, piece: &FallingPiece) -> FallingPiece {
+  let mut dropped = piece.clone();
+  dropped.fall();
+  while valid_position(grid, &dropped) {
+    dropped.fall();
+  }
+  dropped.unfall();
+  dropped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    HandlingSettings,
+    playfield::{Playfield, spawn_tetromino, tetromino::Tetromino},
+    rules::Rules,
+  };
+
+  /// An empty board with a T just spawned.
+  fn t_playfield() -> Playfield {
+    let mut playfield = Playfield::new(0, Rules::default());
+    playfield.falling_piece = spawn_tetromino(Tetromino::T, &playfield.rules);
+    playfield
+  }
+
+  /// The fewest keys for every T placement on an empty board, by the column of its center.
+  fn t_minimums(rotation: Option<RotationType>, columns: std::ops::RangeInclusive<i8>) -> Vec<u32> {
+    let playfield = t_playfield();
+    let spawned = &playfield.falling_piece;
+    columns
+      .map(|x| {
+        let mut placed = FallingPiece::new(Tetromino::T, x, spawned.y);
+        if let Some(rotation) = rotation {
+          placed.rotate(rotation);
+        }
+        let placed = dropped(&playfield.grid, &placed);
+        minimum_keys(
+          &playfield.grid,
+          spawned,
+          &placed,
+          playfield.rules.rotation_system.rotation_system(),
+          false,
+        )
+        .expect("every placement on an empty board is reachable")
+      })
+      .collect()
+  }
+
+  #[test]
+  fn t_placements_take_the_known_minimums() {
+    assert_eq!(t_minimums(None, 1..=8), [1, 2, 1, 0, 1, 2, 2, 1]);
+    assert_eq!(
+      t_minimums(Some(RotationType::Clockwise), 0..=8),
+      [2, 2, 3, 2, 1, 2, 3, 3, 2]
+    );
+    assert_eq!(
+      t_minimums(Some(RotationType::CounterClockwise), 1..=9),
+      [2, 3, 2, 1, 2, 3, 3, 2, 2]
+    );
+    // Without half turns, facing down takes two
+    assert_eq!(
+      t_minimums(Some(RotationType::OneEighty), 1..=8),
+      [3, 4, 3, 2, 3, 4, 4, 3]
+    );
+  }
+
+  #[test]
+  fn das_is_a_single_key() {
+    let h = HandlingSettings::default();
+    let drop = |playfield: &mut Playfield| {
+      let mut input = Input::default();
+      input.press(Action::HardDrop);
+      playfield
+        .update(input, &h, Duration::ZERO)
+        .and_then(|info| info.finesse)
+    };
+
+    // Tapping to the wall takes three keys where holding takes one
+    let mut tapped = t_playfield();
+    for _ in 0..3 {
+      let mut input = Input::default();
+      input.press(Action::Left);
+      tapped.update(input, &h, Duration::ZERO);
+    }
+    let finesse = drop(&mut tapped).expect("straight drops are judged");
+    assert_eq!((finesse.keys, finesse.minimum), (3, 1));
+    assert!(finesse.is_fault());
+
+    let mut held = t_playfield();
+    let mut input = Input::default();
+    input.press(Action::Left);
+    input.hold(Action::Left);
+    held.update(input, &h, Duration::ZERO);
+    let mut input = Input::default();
+    input.hold(Action::Left);
+    held.update(input, &h, h.das);
+    let finesse = drop(&mut held).expect("straight drops are judged");
+    assert_eq!((finesse.keys, finesse.minimum), (1, 1));
+    assert!(!finesse.is_fault());
+  }
+}
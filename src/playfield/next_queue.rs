@@ -0,0 +1,106 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::{
+  randomizer::{AnyRandomizer, Randomizer, RandomizerKind},
+  tetromino::Tetromino,
+};
+
+pub const NEXT_SIZE: usize = 5;
+
+/// The upcoming pieces, dealt by one of the built-in randomizers.
+///
+/// The order only depends on the seed and the randomizer, so two queues built from the same ones
+/// hand out the same pieces on every platform.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NextQueue {
+  queue: VecDeque<Tetromino>,
+  randomizer: AnyRandomizer,
+  rng: ChaCha8Rng,
+  seed: u64,
+  /// Whether only preset pieces are dealt, the queue running dry after them.
+  #[serde(default)]
+  is_fixed: bool,
+}
+
+impl NextQueue {
+  pub fn new(seed: u64, randomizer: RandomizerKind) -> Self {
+    let mut next_queue = Self {
+      queue: VecDeque::with_capacity(NEXT_SIZE + 1),
+      randomizer: randomizer.randomizer(),
+      rng: ChaCha8Rng::seed_from_u64(seed),
+      seed,
+      is_fixed: false,
+    };
+    next_queue.fill();
+    next_queue
+  }
+
+  fn fill(&mut self) {
+    while !self.is_fixed && self.queue.len() <= NEXT_SIZE {
+      let tetromino = self.randomizer.next(&mut self.rng);
+      self.queue.push_back(tetromino);
+    }
+  }
+
+  /// The next piece, or `None` once a fixed sequence has run out.
+  pub fn next_tetromino(&mut self) -> Option<Tetromino> {
+    let tetromino = self.queue.pop_front();
+    self.fill();
+    tetromino
+  }
+
+  /// Deals `pieces`, in order, before going back to the randomizer.
+  pub fn preset(&mut self, pieces: &[Tetromino]) {
+    self.queue = pieces.iter().copied().collect();
+    self.is_fixed = false;
+    self.fill();
+  }
+
+  /// Deals `pieces`, in order, and nothing after them.
+  pub fn preset_fixed(&mut self, pieces: &[Tetromino]) {
+    self.queue = pieces.iter().copied().collect();
+    self.is_fixed = true;
+  }
+
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+
+  /// The piece that [`NextQueue::next_tetromino`] will return.
+  pub fn peek(&self) -> Option<Tetromino> {
+    self.queue.front().copied()
+  }
+
+  /// The visible part of the queue, [`NEXT_SIZE`] pieces long, or shorter when a fixed sequence
+  /// is about to run out.
+  pub fn queue(&self) -> impl Iterator<Item = Tetromino> {
+    self.queue.iter().take(NEXT_SIZE).copied()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fixed_sequences_run_dry() {
+    let mut next_queue = NextQueue::new(0, RandomizerKind::default());
+    next_queue.preset_fixed(&[Tetromino::T, Tetromino::I]);
+    assert!(next_queue.queue().eq([Tetromino::T, Tetromino::I]));
+    assert_eq!(next_queue.next_tetromino(), Some(Tetromino::T));
+    assert_eq!(next_queue.next_tetromino(), Some(Tetromino::I));
+    assert_eq!(next_queue.peek(), None);
+    assert_eq!(next_queue.next_tetromino(), None);
+  }
+
+  #[test]
+  fn presets_go_back_to_the_randomizer() {
+    let mut next_queue = NextQueue::new(0, RandomizerKind::default());
+    next_queue.preset(&[Tetromino::T]);
+    assert_eq!(next_queue.next_tetromino(), Some(Tetromino::T));
+    assert_eq!(next_queue.queue().count(), NEXT_SIZE);
+  }
+}
@@ -0,0 +1,259 @@
+//! Piece generators used to fill the [`NextQueue`](super::next_queue::NextQueue).
+
+use rand::{Rng, RngCore, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use super::tetromino::Tetromino;
+
+/// Decides which piece comes next. All randomness must come from `rng` so that sequences stay
+/// reproducible from the queue's seed.
+pub trait Randomizer {
+  fn next(&mut self, rng: &mut dyn RngCore) -> Tetromino;
+}
+
+/// Deals shuffled bags holding `copies` of every piece plus `extra` random ones.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bag {
+  copies: usize,
+  extra: usize,
+  remaining: Vec<Tetromino>,
+}
+
+impl Bag {
+  pub fn new(copies: usize, extra: usize) -> Self {
+    Self {
+      copies,
+      extra,
+      remaining: Vec::with_capacity(copies * Tetromino::ALL.len() + extra),
+    }
+  }
+}
+
+impl Randomizer for Bag {
+  fn next(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+    if self.remaining.is_empty() {
+      for _ in 0..self.copies {
+        self.remaining.extend(Tetromino::ALL);
+      }
+      for _ in 0..self.extra {
+        self
+          .remaining
+          .push(Tetromino::ALL[rng.random_range(0..Tetromino::ALL.len())]);
+      }
+      self.remaining.shuffle(rng);
+    }
+
+    self.remaining.pop().expect("bags should never be empty")
+  }
+}
+
+/// Every piece is equally likely every time, droughts and floods included.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PureRandom;
+
+impl Randomizer for PureRandom {
+  fn next(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+    Tetromino::ALL[rng.random_range(0..Tetromino::ALL.len())]
+  }
+}
+
+/// The NES generator: rolls an eighth "reroll" value or a repeat of the last piece, then rolls
+/// again once without any check.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct NesReroll {
+  last: Option<Tetromino>,
+}
+
+impl Randomizer for NesReroll {
+  fn next(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+    let roll = rng.random_range(0..=Tetromino::ALL.len());
+    let tetromino = match Tetromino::ALL.get(roll) {
+      Some(&tetromino) if Some(tetromino) != self.last => tetromino,
+      _ => Tetromino::ALL[rng.random_range(0..Tetromino::ALL.len())],
+    };
+
+    self.last = Some(tetromino);
+    tetromino
+  }
+}
+
+/// The TGM generator: rolls up to `rolls` times for a piece missing from the last four dealt,
+/// keeping the last roll if none is found. The first piece is never S, Z or O.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct History {
+  history: [Tetromino; 4],
+  rolls: u32,
+  is_first: bool,
+}
+
+impl History {
+  /// The TGM1 setup: four rolls against a history of four Zs.
+  pub fn tgm1() -> Self {
+    Self {
+      history: [Tetromino::Z; 4],
+      rolls: 4,
+      is_first: true,
+    }
+  }
+
+  /// The TGM2 setup: six rolls against a history of Z, S, S, Z.
+  pub fn tgm2() -> Self {
+    Self {
+      history: [Tetromino::Z, Tetromino::S, Tetromino::S, Tetromino::Z],
+      rolls: 6,
+      is_first: true,
+    }
+  }
+}
+
+impl Randomizer for History {
+  fn next(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+    const FIRST_PIECES: [Tetromino; 4] = [Tetromino::I, Tetromino::T, Tetromino::J, Tetromino::L];
+
+    let tetromino = if self.is_first {
+      self.is_first = false;
+      FIRST_PIECES[rng.random_range(0..FIRST_PIECES.len())]
+    } else {
+      let mut tetromino = Tetromino::ALL[rng.random_range(0..Tetromino::ALL.len())];
+      for _ in 1..self.rolls {
+        if !self.history.contains(&tetromino) {
+          break;
+        }
+        tetromino = Tetromino::ALL[rng.random_range(0..Tetromino::ALL.len())];
+      }
+      tetromino
+    };
+
+    self.history.rotate_left(1);
+    self.history[3] = tetromino;
+    tetromino
+  }
+}
+
+/// The built-in randomizers, as picked in the rules of a game.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum RandomizerKind {
+  #[default]
+  SevenBag,
+  FourteenBag,
+  SevenBagPlusOne,
+  PureRandom,
+  Nes,
+  Tgm1,
+  Tgm2,
+}
+
+impl RandomizerKind {
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::SevenBag => "7-Bag",
+      Self::FourteenBag => "14-Bag",
+      Self::SevenBagPlusOne => "7+1 Bag",
+      Self::PureRandom => "Random",
+      Self::Nes => "NES",
+      Self::Tgm1 => "TGM 4 Rolls",
+      Self::Tgm2 => "TGM 6 Rolls",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::SevenBag => Self::FourteenBag,
+      Self::FourteenBag => Self::SevenBagPlusOne,
+      Self::SevenBagPlusOne => Self::PureRandom,
+      Self::PureRandom => Self::Nes,
+      Self::Nes => Self::Tgm1,
+      Self::Tgm1 => Self::Tgm2,
+      Self::Tgm2 => Self::SevenBag,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::SevenBag => Self::Tgm2,
+      Self::FourteenBag => Self::SevenBag,
+      Self::SevenBagPlusOne => Self::FourteenBag,
+      Self::PureRandom => Self::SevenBagPlusOne,
+      Self::Nes => Self::PureRandom,
+      Self::Tgm1 => Self::Nes,
+      Self::Tgm2 => Self::Tgm1,
+    }
+  }
+
+  pub fn randomizer(self) -> AnyRandomizer {
+    match self {
+      Self::SevenBag => AnyRandomizer::Bag(Bag::new(1, 0)),
+      Self::FourteenBag => AnyRandomizer::Bag(Bag::new(2, 0)),
+      Self::SevenBagPlusOne => AnyRandomizer::Bag(Bag::new(1, 1)),
+      Self::PureRandom => AnyRandomizer::PureRandom(PureRandom),
+      Self::Nes => AnyRandomizer::Nes(NesReroll::default()),
+      Self::Tgm1 => AnyRandomizer::History(History::tgm1()),
+      Self::Tgm2 => AnyRandomizer::History(History::tgm2()),
+    }
+  }
+}
+
+/// Any of the built-in randomizers together with its state.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum AnyRandomizer {
+  Bag(Bag),
+  PureRandom(PureRandom),
+  Nes(NesReroll),
+  History(History),
+}
+
+impl Randomizer for AnyRandomizer {
+  fn next(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+    match self {
+      Self::Bag(bag) => bag.next(rng),
+      Self::PureRandom(pure_random) => pure_random.next(rng),
+      Self::Nes(nes) => nes.next(rng),
+      Self::History(history) => history.next(rng),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::SeedableRng;
+  use rand_chacha::ChaCha8Rng;
+
+  fn deal(kind: RandomizerKind, seed: u64, count: usize) -> Vec<Tetromino> {
+    let mut randomizer = kind.randomizer();
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    (0..count).map(|_| randomizer.next(&mut rng)).collect()
+  }
+
+  #[test]
+  fn seven_bags_deal_every_piece_once() {
+    for seed in 0..10 {
+      for bag in deal(RandomizerKind::SevenBag, seed, 70).chunks(7) {
+        assert!(
+          Tetromino::ALL
+            .iter()
+            .all(|tetromino| bag.contains(tetromino))
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn seeds_fix_the_sequence() {
+    use Tetromino::*;
+    // Replays and saved games count on a seed dealing these same pieces in every version
+    assert_eq!(
+      deal(RandomizerKind::SevenBag, 0, 14),
+      [S, J, Z, O, L, I, T, Z, T, J, L, O, I, S]
+    );
+
+    let mut kind = RandomizerKind::default();
+    loop {
+      assert_eq!(deal(kind, 42, 50), deal(kind, 42, 50));
+      kind = kind.next();
+      if kind == RandomizerKind::default() {
+        break;
+      }
+    }
+  }
+}
@@ -0,0 +1,345 @@
+//! How pieces spawn and which positions they try when rotated next to walls or other minos.
+//!
+//! Every system rotates the piece's map around its center and then applies a translation. Where
+//! the systems differ is in the translations they try: SRS derives them from per-orientation offset
+//! tables, while the others add a short list of kicks to a per-orientation correction that moves
+//! their rotation states away from the SRS ones.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+  falling_piece::{FallingPiece, Orientation, RotationType},
+  tetromino::Tetromino,
+};
+
+pub type OffsetTable = [(i8, i8); 5];
+
+/// One `(x, y)` correction per orientation, in the order up, right, down, left.
+type StateOffsets = [(i8, i8); 4];
+
+pub trait RotationSystem {
+  /// Creates `tetromino` in its spawn state with its rotation center at `(x, y)`.
+  fn spawn(&self, tetromino: Tetromino, x: i8, y: i8) -> FallingPiece;
+
+  /// Rotates `piece`, trying each candidate position in order until one is not `is_blocked`.
+  ///
+  /// Returns the rotated piece together with the index of the candidate that fit, `0` being the
+  /// unkicked rotation.
+  fn rotate(
+    &self,
+    piece: &FallingPiece,
+    rotation: RotationType,
+    is_blocked: &dyn Fn(i32, i32) -> bool,
+  ) -> Option<(FallingPiece, usize)>;
+}
+
+/// The built-in rotation systems, as picked in the rules of a game.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum RotationSystemKind {
+  #[default]
+  Srs,
+  SrsPlus,
+  Ars,
+  Classic,
+}
+
+impl RotationSystemKind {
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Srs => "SRS",
+      Self::SrsPlus => "SRS+",
+      Self::Ars => "ARS",
+      Self::Classic => "Classic",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::Srs => Self::SrsPlus,
+      Self::SrsPlus => Self::Ars,
+      Self::Ars => Self::Classic,
+      Self::Classic => Self::Srs,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::Srs => Self::Classic,
+      Self::SrsPlus => Self::Srs,
+      Self::Ars => Self::SrsPlus,
+      Self::Classic => Self::Ars,
+    }
+  }
+
+  pub fn rotation_system(self) -> &'static dyn RotationSystem {
+    match self {
+      Self::Srs => &Srs,
+      Self::SrsPlus => &SrsPlus,
+      Self::Ars => &Ars,
+      Self::Classic => &Classic,
+    }
+  }
+}
+
+/// The Guideline Super Rotation System.
+pub struct Srs;
+
+impl Srs {
+  fn offsets(tetromino: Tetromino, orientation: Orientation) -> &'static OffsetTable {
+    static I_TABLE: [OffsetTable; 4] = [
+      [(0, 0), (-1, 0), (2, 0), (-1, 0), (2, 0)],
+      [(-1, 0), (0, 0), (0, 0), (0, 1), (0, -2)],
+      [(-1, 1), (1, 1), (-2, 1), (1, 0), (-2, 0)],
+      [(0, 1), (0, 1), (0, 1), (0, -1), (0, 2)],
+    ];
+    static O_TABLE: [OffsetTable; 4] = [
+      [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0)],
+      [(0, -1), (0, -1), (0, -1), (0, -1), (0, -1)],
+      [(-1, -1), (-1, -1), (-1, -1), (-1, -1), (-1, -1)],
+      [(-1, 0), (-1, 0), (-1, 0), (-1, 0), (-1, 0)],
+    ];
+    static DEFAULT_TABLE: [OffsetTable; 4] = [
+      [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0)],
+      [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+      [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0)],
+      [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    ];
+
+    let offset_table = match tetromino {
+      Tetromino::I => &I_TABLE,
+      Tetromino::O => &O_TABLE,
+      _ => &DEFAULT_TABLE,
+    };
+    &offset_table[orientation as usize]
+  }
+
+  /// The translation of an unkicked rotation between two SRS states.
+  fn base(tetromino: Tetromino, from: Orientation, to: Orientation) -> (i8, i8) {
+    let (p1, p2) = (
+      Self::offsets(tetromino, from)[0],
+      Self::offsets(tetromino, to)[0],
+    );
+    (p1.0 - p2.0, p1.1 - p2.1)
+  }
+}
+
+impl RotationSystem for Srs {
+  fn spawn(&self, tetromino: Tetromino, x: i8, y: i8) -> FallingPiece {
+    FallingPiece::new(tetromino, x, y)
+  }
+
+  fn rotate(
+    &self,
+    piece: &FallingPiece,
+    rotation: RotationType,
+    is_blocked: &dyn Fn(i32, i32) -> bool,
+  ) -> Option<(FallingPiece, usize)> {
+    let mut rotated_piece = piece.clone();
+    rotated_piece.rotate(rotation);
+
+    let from = Self::offsets(piece.tetromino, piece.orientation);
+    let to = Self::offsets(piece.tetromino, rotated_piece.orientation);
+    let candidates = from
+      .iter()
+      .zip(to)
+      .map(|(p1, p2)| (p1.0 - p2.0, p1.1 - p2.1));
+    first_fit(rotated_piece, candidates, is_blocked)
+  }
+}
+
+/// SRS as played in TETR.IO: symmetric I kicks and a dedicated table for 180 rotations.
+pub struct SrsPlus;
+
+impl SrsPlus {
+  fn i_kicks(from: Orientation, to: Orientation) -> &'static [(i8, i8)] {
+    use Orientation::{Down, Left, Right, Up};
+    match (from, to) {
+      (Up, Right) => &[(0, 0), (1, 0), (-2, 0), (-2, -1), (1, 2)],
+      (Right, Up) => &[(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
+      (Right, Down) => &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+      (Down, Right) => &[(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
+      (Down, Left) => &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+      (Left, Down) => &[(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
+      (Left, Up) => &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+      (Up, Left) => &[(0, 0), (-1, 0), (2, 0), (2, -1), (-1, 2)],
+      _ => &[(0, 0)],
+    }
+  }
+
+  fn one_eighty_kicks(from: Orientation) -> &'static [(i8, i8)] {
+    match from {
+      Orientation::Up => &[(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+      Orientation::Right => &[(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+      Orientation::Down => &[(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+      Orientation::Left => &[(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+    }
+  }
+}
+
+impl RotationSystem for SrsPlus {
+  fn spawn(&self, tetromino: Tetromino, x: i8, y: i8) -> FallingPiece {
+    Srs.spawn(tetromino, x, y)
+  }
+
+  fn rotate(
+    &self,
+    piece: &FallingPiece,
+    rotation: RotationType,
+    is_blocked: &dyn Fn(i32, i32) -> bool,
+  ) -> Option<(FallingPiece, usize)> {
+    let kicks = match (rotation, piece.tetromino) {
+      (_, Tetromino::O) => &[(0, 0)],
+      (RotationType::OneEighty, _) => Self::one_eighty_kicks(piece.orientation),
+      (_, Tetromino::I) => {
+        let mut rotated_piece = piece.clone();
+        rotated_piece.rotate(rotation);
+        Self::i_kicks(piece.orientation, rotated_piece.orientation)
+      }
+      _ => return Srs.rotate(piece, rotation, is_blocked),
+    };
+
+    let mut rotated_piece = piece.clone();
+    rotated_piece.rotate(rotation);
+    let base = Srs::base(
+      piece.tetromino,
+      piece.orientation,
+      rotated_piece.orientation,
+    );
+    let candidates = kicks.iter().map(|kick| (base.0 + kick.0, base.1 + kick.1));
+    first_fit(rotated_piece, candidates, is_blocked)
+  }
+}
+
+/// The Arika Rotation System from the TGM series: bottom-aligned states, L, J and T spawning flat
+/// side up and single-column kicks to the right then to the left.
+pub struct Ars;
+
+impl Ars {
+  fn state_offsets(tetromino: Tetromino) -> &'static StateOffsets {
+    match tetromino {
+      Tetromino::I => &[(0, 0), (-1, 0), (-1, 0), (-1, 1)],
+      Tetromino::O => &[(0, 0), (0, -1), (-1, -1), (-1, 0)],
+      Tetromino::S => &[(0, 1), (1, 0), (0, 0), (0, 0)],
+      Tetromino::Z => &[(0, 1), (0, 0), (0, 0), (-1, 0)],
+      Tetromino::T | Tetromino::J | Tetromino::L => &[(0, 1), (0, 0), (0, 0), (0, 0)],
+    }
+  }
+
+  /// L, J and T may not kick when the first blocked mino, read top to bottom and left to right,
+  /// sits in the middle column of their bounding box.
+  fn blocked_in_center_column(piece: &FallingPiece, is_blocked: &dyn Fn(i32, i32) -> bool) -> bool {
+    let mut minos = piece.map;
+    minos.sort_by_key(|&(cx, cy)| (-cy, cx));
+    minos
+      .iter()
+      .find(|&&(cx, cy)| is_blocked((piece.x + cx) as i32, (piece.y + cy) as i32))
+      .is_some_and(|&(cx, _)| cx == 0)
+  }
+}
+
+impl RotationSystem for Ars {
+  fn spawn(&self, tetromino: Tetromino, x: i8, y: i8) -> FallingPiece {
+    flat_side_up_spawn(tetromino, x, y, Self::state_offsets(tetromino))
+  }
+
+  fn rotate(
+    &self,
+    piece: &FallingPiece,
+    rotation: RotationType,
+    is_blocked: &dyn Fn(i32, i32) -> bool,
+  ) -> Option<(FallingPiece, usize)> {
+    let offsets = Self::state_offsets(piece.tetromino);
+    let mut rotated_piece = piece.clone();
+    rotated_piece.rotate(rotation);
+    let base = state_base(offsets, piece.orientation, rotated_piece.orientation);
+
+    let mut unkicked = rotated_piece.clone();
+    unkicked.translate(base);
+    let can_kick = match piece.tetromino {
+      Tetromino::I | Tetromino::O => false,
+      Tetromino::T | Tetromino::J | Tetromino::L => {
+        !Self::blocked_in_center_column(&unkicked, is_blocked)
+      }
+      Tetromino::S | Tetromino::Z => true,
+    };
+
+    let kicks: &[(i8, i8)] = if can_kick {
+      &[(0, 0), (1, 0), (-1, 0)]
+    } else {
+      &[(0, 0)]
+    };
+    let candidates = kicks.iter().map(|kick| (base.0 + kick.0, base.1 + kick.1));
+    first_fit(rotated_piece, candidates, is_blocked)
+  }
+}
+
+/// A right-handed system without any kicks, as found on the NES.
+pub struct Classic;
+
+impl Classic {
+  fn state_offsets(tetromino: Tetromino) -> &'static StateOffsets {
+    match tetromino {
+      Tetromino::I => &[(0, 1), (-1, 0), (-1, 1), (-1, 1)],
+      Tetromino::O => &[(0, 0), (0, -1), (-1, -1), (-1, 0)],
+      Tetromino::S | Tetromino::Z => &[(0, 1), (0, 0), (0, 0), (-1, 0)],
+      Tetromino::T | Tetromino::J | Tetromino::L => &[(0, 0), (0, 0), (0, 0), (0, 0)],
+    }
+  }
+}
+
+impl RotationSystem for Classic {
+  fn spawn(&self, tetromino: Tetromino, x: i8, y: i8) -> FallingPiece {
+    flat_side_up_spawn(tetromino, x, y, Self::state_offsets(tetromino))
+  }
+
+  fn rotate(
+    &self,
+    piece: &FallingPiece,
+    rotation: RotationType,
+    is_blocked: &dyn Fn(i32, i32) -> bool,
+  ) -> Option<(FallingPiece, usize)> {
+    let offsets = Self::state_offsets(piece.tetromino);
+    let mut rotated_piece = piece.clone();
+    rotated_piece.rotate(rotation);
+    let base = state_base(offsets, piece.orientation, rotated_piece.orientation);
+    first_fit(rotated_piece, [base].into_iter(), is_blocked)
+  }
+}
+
+/// Spawns L, J and T pointing down and every other piece in its corrected up state.
+fn flat_side_up_spawn(tetromino: Tetromino, x: i8, y: i8, offsets: &StateOffsets) -> FallingPiece {
+  let mut piece = FallingPiece::new(tetromino, x, y);
+  if let Tetromino::T | Tetromino::J | Tetromino::L = tetromino {
+    piece.rotate(RotationType::OneEighty);
+  }
+
+  let (dx, dy) = offsets[piece.orientation as usize];
+  piece.translate((-dx, -dy));
+  piece
+}
+
+fn state_base(offsets: &StateOffsets, from: Orientation, to: Orientation) -> (i8, i8) {
+  let (p1, p2) = (offsets[from as usize], offsets[to as usize]);
+  (p1.0 - p2.0, p1.1 - p2.1)
+}
+
+fn first_fit(
+  rotated_piece: FallingPiece,
+  candidates: impl Iterator<Item = (i8, i8)>,
+  is_blocked: &dyn Fn(i32, i32) -> bool,
+) -> Option<(FallingPiece, usize)> {
+  candidates
+    .map(|offset| {
+      let mut translated_piece = rotated_piece.clone();
+      translated_piece.translate(offset);
+      translated_piece
+    })
+    .enumerate()
+    .find(|(_, piece)| {
+      piece
+        .map
+        .iter()
+        .all(|&(cx, cy)| !is_blocked((piece.x + cx) as i32, (piece.y + cy) as i32))
+    })
+    .map(|(kick, piece)| (piece, kick))
+}
@@ -1,6 +1,6 @@
-use raylib::color::Color;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
 pub enum Tetromino {
   I,
   O,
@@ -11,20 +11,22 @@ pub enum Tetromino {
   L,
 }
 
+/// The offsets of the four minos from the piece's rotation center, `y` pointing up.
 pub type TetrominoMap = [(i8, i8); 4];
 
 impl Tetromino {
-  pub fn color(self) -> Color {
-    match self {
-      Self::I => Color::new(49, 199, 239, 255),
-      Self::O => Color::new(247, 211, 8, 255),
-      Self::T => Color::new(173, 77, 156, 255),
-      Self::Z => Color::new(239, 32, 41, 255),
-      Self::S => Color::new(66, 182, 66, 255),
-      Self::J => Color::new(90, 101, 173, 255),
-      Self::L => Color::new(239, 121, 33, 255),
-    }
-  }
+  /// Every piece, in declaration order.
+  pub const ALL: [Self; 7] = [
+    Tetromino::I,
+    Tetromino::O,
+    Tetromino::T,
+    Tetromino::Z,
+    Tetromino::S,
+    Tetromino::J,
+    Tetromino::L,
+  ];
+
+  /// The shape of the piece as it spawns.
   pub fn initial_map(self) -> TetrominoMap {
     match self {
       Tetromino::I => [(-1, 0), (0, 0), (1, 0), (2, 0)],
@@ -37,6 +39,20 @@ impl Tetromino {
     }
   }
 
+  /// The piece named by a letter, as written in puzzle files.
+  pub fn from_char(letter: char) -> Option<Self> {
+    match letter {
+      'I' => Some(Tetromino::I),
+      'O' => Some(Tetromino::O),
+      'T' => Some(Tetromino::T),
+      'S' => Some(Tetromino::S),
+      'Z' => Some(Tetromino::Z),
+      'J' => Some(Tetromino::J),
+      'L' => Some(Tetromino::L),
+      _ => None,
+    }
+  }
+
   pub fn name(self) -> &'static str {
     match self {
       Tetromino::I => "I",
@@ -1,12 +1,28 @@
 mod gameplay;
 mod main_menu;
+mod records;
+mod replays;
+mod saves;
 mod settings;
 
-use std::time::Duration;
+use std::{fs::read_to_string, time::Duration};
 
 use raylib::{RaylibHandle, RaylibThread, init, prelude::RaylibDrawHandle};
 
-use gameplay::{single_player::SinglePlayer, two_player::TwoPlayer};
+use rustris::mode::{
+  Mode, ModeKind,
+  cheese::Cheese,
+  master::Master,
+  pc_training::PcTraining,
+  puzzle::{Puzzle, Puzzles, parse_puzzles},
+  sprint::Sprint,
+  survival::Survival,
+  ultra::Ultra,
+};
+
+use gameplay::{
+  editor::Editor, replay_viewer::ReplayViewer, single_player::SinglePlayer, two_player::TwoPlayer,
+};
 use main_menu::MainMenu;
 use settings::{Resolution, SettingsMenu, config};
 
@@ -17,6 +33,8 @@ enum App {
   SettingsMenu(SettingsMenu),
   SinglePlayer(SinglePlayer),
   TwoPlayer(TwoPlayer),
+  ReplayViewer(ReplayViewer),
+  Editor(Editor),
 }
 
 impl App {
@@ -26,6 +44,8 @@ impl App {
       App::SettingsMenu(settings_menu) => settings_menu.draw(rld),
       App::SinglePlayer(single_player) => single_player.draw(rld),
       App::TwoPlayer(two_player) => two_player.draw(rld),
+      App::ReplayViewer(replay_viewer) => replay_viewer.draw(rld),
+      App::Editor(editor) => editor.draw(rld),
     }
   }
 
@@ -35,6 +55,8 @@ impl App {
       App::SettingsMenu(settings_menu) => settings_menu.update(rl),
       App::SinglePlayer(single_player) => single_player.update(dt, rl),
       App::TwoPlayer(two_player) => two_player.update(dt, rl),
+      App::ReplayViewer(replay_viewer) => replay_viewer.update(dt, rl),
+      App::Editor(editor) => editor.update(dt, rl),
     }
   }
 
@@ -44,6 +66,8 @@ impl App {
       App::SettingsMenu(settings_menu) => settings_menu.should_stop_running(rl),
       App::SinglePlayer(single_player) => single_player.should_stop_running(rl),
       App::TwoPlayer(two_player) => two_player.should_stop_running(rl),
+      App::ReplayViewer(replay_viewer) => replay_viewer.should_stop_running(rl),
+      App::Editor(editor) => editor.should_stop_running(rl),
     }
   }
 }
@@ -83,14 +107,50 @@ impl Raytris {
             return;
           }
           Option::Settings => App::SettingsMenu(SettingsMenu::new()),
-          Option::SinglePlayer => {
-            App::SinglePlayer(SinglePlayer::new(config().handling_settings, &self.rl))
+          Option::SinglePlayer => App::SinglePlayer(SinglePlayer::new(
+            config().handling_settings,
+            config().rules,
+            new_mode(config().mode),
+            config().save_slot,
+            config().finesse_trainer,
+            &self.rl,
+          )),
+          Option::Resume => {
+            let slot = config().save_slot;
+            match saves::load(slot) {
+              Some(saved_game) => App::SinglePlayer(SinglePlayer::resume(
+                saved_game,
+                slot,
+                config().finesse_trainer,
+                &self.rl,
+              )),
+              None => App::MainMenu(MainMenu::new()),
+            }
           }
           Option::TwoPlayer => App::TwoPlayer(TwoPlayer::new(
             config().handling_settings,
             config().handling_settings,
+            config().rules,
+            &self.rl,
+          )),
+          Option::Replay => match replays::load() {
+            Some(replay) => App::ReplayViewer(ReplayViewer::new(replay, &self.rl)),
+            None => App::MainMenu(MainMenu::new()),
+          },
+          Option::Editor => App::Editor(Editor::new(config().rules, &self.rl)),
+        }
+      }
+      App::Editor(editor) => {
+        self.app = match editor.position_to_play() {
+          Some(position) => App::SinglePlayer(SinglePlayer::from_position(
+            config().handling_settings,
+            config().rules,
+            position,
+            config().save_slot,
+            config().finesse_trainer,
             &self.rl,
           )),
+          None => App::MainMenu(MainMenu::new()),
         }
       }
       _ => self.app = App::MainMenu(MainMenu::new()),
@@ -112,3 +172,33 @@ impl Raytris {
     }
   }
 }
+
+fn new_mode(kind: ModeKind) -> Mode {
+  match kind {
+    ModeKind::Marathon => Mode::Marathon,
+    ModeKind::Sprint => Mode::Sprint(Sprint::new(config().sprint_goal)),
+    ModeKind::Ultra => Mode::Ultra(Ultra::new(config().ultra_limit)),
+    ModeKind::Cheese => Mode::Cheese(Box::new(Cheese::new(config().cheese_goal))),
+    ModeKind::Survival => Mode::Survival(Box::new(Survival::new())),
+    ModeKind::Classic => Mode::Classic,
+    ModeKind::Master => Mode::Master(Master::new()),
+    ModeKind::Puzzle => Mode::Puzzle(Box::new(Puzzles::new(load_puzzles()))),
+    ModeKind::PcTraining => Mode::PcTraining(Box::new(PcTraining::new(config().pc_start))),
+  }
+}
+
+const PUZZLES_FILE_NAME: &str = "puzzles.raytris";
+
+/// The puzzles in [`PUZZLES_FILE_NAME`], or the built-in ones when there is no such file or it
+/// cannot be read.
+fn load_puzzles() -> Vec<Puzzle> {
+  if let Ok(text) = read_to_string(PUZZLES_FILE_NAME) {
+    match parse_puzzles(&text) {
+      Ok(puzzles) => return puzzles,
+      Err(error) => eprintln!("{PUZZLES_FILE_NAME}: {error}"),
+    }
+  }
+
+  const BUILT_IN_PUZZLES: &str = include_str!("raytris/puzzles.json");
+  parse_puzzles(BUILT_IN_PUZZLES).expect("built-in puzzles should be valid")
+}
@@ -1,34 +1,76 @@
+pub mod editor;
 mod game;
-mod line_clear_message;
+mod mode;
 mod playfield;
+pub mod replay_viewer;
 pub mod single_player;
 pub mod two_player;
 
-use raylib::{RaylibHandle, color::Color, math::Vector2};
-use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use raylib::{RaylibHandle, color::Color, math::Vector2, prelude::RaylibDrawHandle};
 
-type Input = fn(&RaylibHandle) -> bool;
+use rustris::{
+  input::{Action, Input},
+  playfield::BoardSize,
+};
+
+type Key = fn(&RaylibHandle) -> bool;
 struct Controller {
-  restart: Input,
-  swap: Input,
-  left: Input,
-  right: Input,
-  left_das: Input,
-  right_das: Input,
-  clockwise: Input,
-  counter_clockwise: Input,
-  one_eighty: Input,
-  hard_drop: Input,
-  soft_drop: Input,
-  undo: Input,
-  pause: Input,
-  quit: Input,
+  restart: Key,
+  swap: Key,
+  left: Key,
+  right: Key,
+  left_das: Key,
+  right_das: Key,
+  clockwise: Key,
+  counter_clockwise: Key,
+  one_eighty: Key,
+  hard_drop: Key,
+  soft_drop: Key,
+  undo: Key,
+  copy_board: Key,
+  copy_history: Key,
+  paste_board: Key,
+  pause: Key,
+  quit: Key,
+}
+
+impl Controller {
+  fn input(&self, rl: &RaylibHandle) -> Input {
+    let pressed = [
+      (Action::Swap, self.swap),
+      (Action::Left, self.left),
+      (Action::Right, self.right),
+      (Action::Clockwise, self.clockwise),
+      (Action::CounterClockwise, self.counter_clockwise),
+      (Action::OneEighty, self.one_eighty),
+      (Action::HardDrop, self.hard_drop),
+    ];
+    let held = [
+      (Action::Left, self.left_das),
+      (Action::Right, self.right_das),
+      (Action::SoftDrop, self.soft_drop),
+    ];
+
+    let mut input = Input::default();
+    for (action, key) in pressed {
+      if key(rl) {
+        input.press(action);
+      }
+    }
+    for (action, key) in held {
+      if key(rl) {
+        input.hold(action);
+      }
+    }
+    input
+  }
 }
 
 struct DrawingDetails {
   block_length: f32,
   position: Vector2,
+  width: i32,
+  visible_height: i32,
   font_size: i32,
   font_size_big: i32,
   font_size_small: i32,
@@ -45,36 +87,35 @@ impl DrawingDetails {
   const PIECE_BOX_COLOR: Color = Color::BLACK;
   const DARKEN_COLOR: Color = Color::new(0, 0, 0, 100);
   const BACKGROUND_COLOR: Color = Color::LIGHTGRAY;
+  const GARBAGE_COLOR: Color = Color::LIGHTGRAY;
+  const GARBAGE_METER_COLOR: Color = Color::RED;
   const LEFT_BORDER: i32 = -10;
+  /// The columns taken by the next queue, right of the board.
+  const RIGHT_BORDER: i32 = 7;
+
+  /// Lays out a board of the given size, with everything drawn around it, in an `area` of the
+  /// screen centered on `center`.
+  fn new(board: BoardSize, area: Vector2, center: Vector2) -> Self {
+    let columns = board.width - Self::LEFT_BORDER + Self::RIGHT_BORDER;
+    let block_length = (Self::HEIGHT_SCALE_FACTOR * area.y / board.visible_height as f32)
+      .min(area.x / columns as f32);
+    let board_vector = Vector2 {
+      x: board.width as f32,
+      y: board.visible_height as f32,
+    };
 
-  fn new(block_length: f32, position: Vector2) -> Self {
     Self {
       block_length,
       font_size: block_length as i32 * 2,
       font_size_big: block_length as i32 * 5,
       font_size_small: block_length as i32,
-      position,
+      position: center - board_vector * block_length / 2.0,
+      width: board.width,
+      visible_height: board.visible_height,
     }
   }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct HandlingSettings {
-  pub gravity: Duration,
-  pub soft_drop: Duration,
-  pub das: Duration,
-  pub lock_delay: Duration,
-  pub lock_delay_resets: u32,
-}
-
-impl Default for HandlingSettings {
-  fn default() -> Self {
-    Self {
-      gravity: Duration::from_millis(330),
-      soft_drop: Duration::from_millis(160),
-      das: Duration::from_millis(140),
-      lock_delay: Duration::from_millis(500),
-      lock_delay_resets: 15,
-    }
-  }
+trait Draw {
+  fn draw(&self, d: &DrawingDetails, rld: &mut RaylibDrawHandle);
 }
@@ -0,0 +1,302 @@
+use std::time::Duration;
+
+use raylib::{
+  RaylibHandle,
+  color::Color,
+  consts::{KeyboardKey, MouseButton},
+  math::Rectangle,
+  prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use rustris::{
+  fumen::{self, FumenError},
+  playfield::{Grid, Mino, Position, tetromino::Tetromino},
+  rules::{Rules, Style},
+};
+
+use super::{
+  DrawingDetails,
+  game::screen_vector,
+  playfield::{block_at, draw_grid, draw_hold_piece, draw_next_queue, get_block, mino_color},
+};
+
+/// The keys picking each piece, as brush, queue piece or hold piece.
+const PIECE_KEYS: [(KeyboardKey, Tetromino); 7] = [
+  (KeyboardKey::KEY_I, Tetromino::I),
+  (KeyboardKey::KEY_O, Tetromino::O),
+  (KeyboardKey::KEY_T, Tetromino::T),
+  (KeyboardKey::KEY_S, Tetromino::S),
+  (KeyboardKey::KEY_Z, Tetromino::Z),
+  (KeyboardKey::KEY_J, Tetromino::J),
+  (KeyboardKey::KEY_L, Tetromino::L),
+];
+
+/// How long notices stay on screen.
+const NOTICE_TIME: Duration = Duration::from_secs(2);
+
+/// A screen to paint a board and pick the pieces to play on it.
+pub struct Editor {
+  /// The visible rows of the board, from the bottom one up.
+  grid: Grid,
+  /// The falling piece first, then the queue.
+  pieces: Vec<Tetromino>,
+  hold: Option<Tetromino>,
+  style: Style,
+  /// What painting a cell fills it with.
+  brush: Mino,
+  /// The cell painted from the keyboard, as column and row.
+  cursor: (i32, i32),
+  /// Whether to start playing on the board once the editor closes.
+  play: bool,
+  /// A short message about the last copy or paste, with how long it stays on screen.
+  notice: Option<(String, Duration)>,
+  drawing_details: DrawingDetails,
+}
+
+impl Editor {
+  pub fn new(rules: Rules, rl: &RaylibHandle) -> Self {
+    let board = rules.board;
+    let screen = screen_vector(rl);
+    Self {
+      grid: vec![vec![None; board.width as usize]; board.visible_height as usize],
+      pieces: Vec::new(),
+      hold: None,
+      style: rules.style,
+      brush: Mino::Garbage,
+      cursor: (0, 0),
+      play: false,
+      notice: None,
+      drawing_details: DrawingDetails::new(board, screen, screen / 2.0),
+    }
+  }
+
+  pub fn update(&mut self, dt: Duration, rl: &mut RaylibHandle) {
+    if let Some((_, time)) = &mut self.notice {
+      *time = time.saturating_sub(dt);
+    }
+    self.notice.take_if(|(_, time)| time.is_zero());
+
+    let control = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL);
+    let shift = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT);
+    if control && rl.is_key_pressed(KeyboardKey::KEY_C) {
+      self.copy(rl);
+      return;
+    }
+    if control && rl.is_key_pressed(KeyboardKey::KEY_V) {
+      self.paste(rl);
+      return;
+    }
+
+    for (key, tetromino) in PIECE_KEYS {
+      if !rl.is_key_pressed(key) {
+        continue;
+      }
+      if control {
+        if self.style != Style::Classic {
+          self.hold = Some(tetromino);
+        }
+      } else if shift {
+        self.pieces.push(tetromino);
+      } else {
+        self.brush = Mino::Piece(tetromino);
+      }
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_G) {
+      self.brush = Mino::Garbage;
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+      self.pieces.pop();
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_DELETE) {
+      self.hold = None;
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_R) {
+      self.grid.iter_mut().flatten().for_each(|cell| *cell = None);
+    }
+
+    let d = &self.drawing_details;
+    let (x, y) = &mut self.cursor;
+    if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+      *x = (*x - 1).max(0);
+    } else if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+      *x = (*x + 1).min(d.width - 1);
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+      *y = (*y - 1).max(0);
+    } else if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+      *y = (*y + 1).min(d.visible_height - 1);
+    }
+    if rl.is_key_down(KeyboardKey::KEY_SPACE) {
+      self.paint(self.cursor, Some(self.brush));
+    } else if rl.is_key_down(KeyboardKey::KEY_X) {
+      self.paint(self.cursor, None);
+    }
+
+    let mouse = block_at(rl.get_mouse_position(), &self.drawing_details);
+    if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+      self.paint(mouse, Some(self.brush));
+    } else if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_RIGHT) {
+      self.paint(mouse, None);
+    }
+
+    if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+      self.play = true;
+    }
+  }
+
+  /// Fills the cell at `(x, y)` with `mino`, unless it is off the board.
+  fn paint(&mut self, (x, y): (i32, i32), mino: Option<Mino>) {
+    if let Some(cell) = usize::try_from(y)
+      .ok()
+      .and_then(|y| self.grid.get_mut(y))
+      .and_then(|row| row.get_mut(usize::try_from(x).ok()?))
+    {
+      *cell = mino;
+    }
+  }
+
+  fn position(&self) -> Position {
+    Position {
+      rows: self.grid.clone(),
+      pieces: self.pieces.clone(),
+      hold: self.hold,
+    }
+  }
+
+  /// Puts the board on the clipboard as a fumen.
+  fn copy(&mut self, rl: &mut RaylibHandle) {
+    let copied = fumen::encode(&[self.position()])
+      .map_err(|error| error.to_string())
+      .and_then(|fumen| {
+        rl.set_clipboard_text(&fumen)
+          .map_err(|error| error.to_string())
+      });
+    let notice = match copied {
+      Ok(()) => "COPIED BOARD".to_string(),
+      Err(error) => error,
+    };
+    self.notice = Some((notice, NOTICE_TIME));
+  }
+
+  /// Replaces the board and pieces with the ones of the fumen on the clipboard. Rows above the
+  /// visible ones are left out.
+  fn paste(&mut self, rl: &RaylibHandle) {
+    let width = self.drawing_details.width as usize;
+    let pasted = rl
+      .get_clipboard_text()
+      .map_err(|error| error.to_string())
+      .and_then(|text| fumen::decode(&text).map_err(|error| error.to_string()))
+      .and_then(|position| match width {
+        10 => Ok(position),
+        _ => Err(FumenError::Width(width).to_string()),
+      });
+    let position = match pasted {
+      Ok(position) => position,
+      Err(error) => {
+        self.notice = Some((error, NOTICE_TIME));
+        return;
+      }
+    };
+
+    for (y, row) in self.grid.iter_mut().enumerate() {
+      match position.rows.get(y) {
+        Some(pasted_row) => row.copy_from_slice(pasted_row),
+        None => row.fill(None),
+      }
+    }
+    self.pieces = position.pieces;
+    if self.style != Style::Classic {
+      self.hold = position.hold;
+    }
+    self.notice = Some(("PASTED BOARD".to_string(), NOTICE_TIME));
+  }
+
+  pub fn draw(&self, rld: &mut RaylibDrawHandle) {
+    let d = &self.drawing_details;
+    rld.clear_background(DrawingDetails::BACKGROUND_COLOR);
+    draw_grid(&self.grid, d, rld);
+    draw_next_queue(self.pieces.iter().copied(), d, rld);
+    if self.style != Style::Classic {
+      draw_hold_piece(self.hold, true, d, rld);
+    }
+
+    const CURSOR_COLOR: Color = Color::WHITE;
+    let (x, y) = self.cursor;
+    let corner = get_block(x, y, d);
+    let cursor = Rectangle {
+      x: corner.x,
+      y: corner.y,
+      width: d.block_length,
+      height: d.block_length,
+    };
+    rld.draw_rectangle_lines_ex(cursor, d.block_length / 8.0, CURSOR_COLOR);
+
+    const EDITOR_COLOR: Color = Color::DARKBLUE;
+    let font_size = d.font_size;
+    let font_size_small = d.font_size_small;
+    let brush_name = match self.brush {
+      Mino::Piece(tetromino) => tetromino.name(),
+      Mino::Garbage => "Garbage",
+    };
+    let brush = format!("BOARD EDITOR  BRUSH {brush_name}");
+    rld.draw_text(
+      &brush,
+      font_size_small,
+      font_size_small,
+      font_size,
+      EDITOR_COLOR,
+    );
+    let brush_width = rld.measure_text(&brush, font_size);
+    let swatch = Rectangle {
+      x: (2 * font_size_small + brush_width) as f32,
+      y: font_size_small as f32,
+      width: font_size as f32,
+      height: font_size as f32,
+    };
+    rld.draw_rectangle_rec(swatch, mino_color(self.brush));
+
+    let pieces: String = self
+      .pieces
+      .iter()
+      .map(|tetromino| tetromino.name())
+      .collect();
+    let pieces = format!("PIECES {pieces}");
+    rld.draw_text(
+      &pieces,
+      font_size_small,
+      font_size_small + font_size,
+      font_size_small,
+      EDITOR_COLOR,
+    );
+    if let Some((notice, _)) = &self.notice {
+      const NOTICE_COLOR: Color = Color::SKYBLUE;
+      rld.draw_text(
+        notice,
+        font_size_small,
+        2 * font_size_small + font_size,
+        font_size_small,
+        NOTICE_COLOR,
+      );
+    }
+
+    const CONTROLS: [&str; 2] = [
+      "Mouse or arrows and Space/X: paint and erase  IOTSZJL/G: brush  R: clear board",
+      "Shift+piece: add to queue  Backspace: remove  Ctrl+piece: hold  Delete: empty hold  \
+       Ctrl+C/V: copy/paste fumen  Enter: play  Esc: quit",
+    ];
+    let mut y = rld.get_render_height() - 3 * font_size_small;
+    for controls in CONTROLS {
+      rld.draw_text(controls, font_size_small, y, font_size_small, EDITOR_COLOR);
+      y += font_size_small;
+    }
+  }
+
+  /// The position to start playing on, once the player asked to.
+  pub fn position_to_play(&self) -> Option<Position> {
+    self.play.then(|| self.position())
+  }
+
+  pub fn should_stop_running(&self, rl: &RaylibHandle) -> bool {
+    self.play || rl.is_key_pressed(KeyboardKey::KEY_ESCAPE)
+  }
+}
@@ -3,194 +3,208 @@ use std::time::Duration;
 use raylib::{
   RaylibHandle,
   color::Color,
-  math::Vector2,
+  math::{Rectangle, Vector2},
   prelude::{RaylibDraw, RaylibDrawHandle},
 };
 
-use super::{
-  Controller, DrawingDetails, HandlingSettings,
+use rustris::{
+  game::Game,
   line_clear_message::{LineClearMessage, MessageType, SpinType},
-  playfield::{Playfield, UpdateInfo, VISIBLE_HEIGHT, WIDTH},
+  playfield::finesse::Finesse,
 };
 
-#[derive(Clone)]
-pub struct Game {
-  playfield: Playfield,
-  combo: u32,
-  score: u64,
-  b2b: u32,
-  message: LineClearMessage,
-}
+use super::{Draw, DrawingDetails, mode::format_time, playfield::tetromino_color};
 
-impl Game {
-  pub fn new() -> Self {
-    Game {
-      playfield: Playfield::new(),
-      combo: 0,
-      score: 0,
-      b2b: 0,
-      message: LineClearMessage::empty(),
-    }
-  }
+impl Draw for Game {
+  fn draw(&self, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+    self.playfield().draw(drawing_details, rld);
 
-  pub fn update(
-    &mut self,
-    dt: Duration,
-    controller: &Controller,
-    settings: &HandlingSettings,
-    rl: &RaylibHandle,
-  ) -> bool {
-    self.message.remaining_time = self.message.remaining_time.saturating_sub(dt);
-
-    if let Some(update_info) = self.playfield.update(controller, settings, dt, rl) {
-      self.update_score(update_info);
-      true
-    } else {
-      false
+    if self.pending_garbage() > 0 {
+      draw_garbage_meter(self, drawing_details, rld);
     }
-  }
-
-  pub fn reset(&mut self) {
-    self.playfield = Playfield::new();
-    self.combo = 0;
-    self.b2b = 0;
-    self.message = LineClearMessage::empty();
-  }
-
-  pub fn has_lost(&self) -> bool {
-    self.playfield.has_lost()
-  }
 
-  fn update_score(&mut self, update_info: UpdateInfo) {
-    let UpdateInfo {
-      cleared_lines,
-      spin,
-      is_all_clear,
-    } = update_info;
-
-    let message = match cleared_lines {
-      0 => None,
-      1 => Some(MessageType::Single),
-      2 => Some(MessageType::Double),
-      3 => Some(MessageType::Triple),
-      4 => Some(MessageType::Tetris),
-      _ => panic!("cleared more than 4 lines at the same time"),
-    };
-
-    self.message = LineClearMessage::new(message, spin);
-
-    if cleared_lines == 0 {
-      self.combo = 0;
-    } else {
-      self.combo += 1;
-      if cleared_lines == 4 || spin.is_some() {
-        self.b2b += 1;
-      } else {
-        self.b2b = 0;
-      }
+    if self.message().remaining_time > Duration::ZERO {
+      draw_message(self, drawing_details, rld);
     }
 
-    self.score += (self.combo * 50) as u64;
-    let b2b_factor = if self.b2b >= 2 { 3 } else { 2 };
-    const SCORE_TABLE: [[u64; 5]; 3] = [
-      /* cleared:  0   1    2    3    4  */
-      /*NoSpin */ [0, 100, 300, 500, 800],
-      /*Mini   */ [100, 200, 400, 0, 0],
-      /*Proper */ [400, 800, 1200, 1600, 0],
-    ];
-    let spin_index = match spin.map(|(_, spin_type)| spin_type) {
-      None => 0,
-      Some(SpinType::Mini) => 1,
-      Some(SpinType::Proper) => 2,
-    };
-    self.score += b2b_factor * SCORE_TABLE[spin_index][cleared_lines as usize] / 2;
-
-    if is_all_clear {
-      self.message.message = Some(MessageType::AllClear);
-      self.score += 3500 * b2b_factor / 2;
+    if let Some((finesse, remaining_time)) = self.finesse_fault() {
+      draw_finesse_fault(finesse, remaining_time, drawing_details, rld);
     }
-  }
-
-  pub fn draw(&self, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
-    self.playfield.draw(drawing_details, rld);
 
-    if self.message.remaining_time > Duration::ZERO {
-      self.draw_message(drawing_details, rld);
+    if self.combo() >= 2 {
+      draw_combo(self, drawing_details, rld);
     }
 
-    if self.combo >= 2 {
-      self.draw_combo(drawing_details, rld);
+    if self.b2b() >= 2 {
+      draw_b2b(self, drawing_details, rld);
     }
 
-    if self.b2b >= 2 {
-      self.draw_b2b(drawing_details, rld);
-    }
+    draw_score(self, drawing_details, rld);
+  }
+}
 
-    self.draw_score(drawing_details, rld);
+fn message_color(message: MessageType) -> Color {
+  match message {
+    MessageType::Single => Color::new(0, 0, 0, 255),
+    MessageType::Double => Color::new(235, 149, 52, 255),
+    MessageType::Triple => Color::new(88, 235, 52, 255),
+    MessageType::Tetris => Color::new(52, 164, 236, 255),
+    MessageType::AllClear => Color::new(235, 52, 213, 255),
   }
+}
 
-  fn draw_message(&self, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
-    const MAX_DURATION: f32 = LineClearMessage::DURATION.as_secs_f32();
-    let duration = self.message.remaining_time.as_secs_f32();
-    let alpha = (255.0 * duration / MAX_DURATION) as u8;
-    if let Some(message) = self.message.message {
-      let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 4, drawing_details);
-      let (msg, mut color) = message.info();
-      color.a = alpha;
-      rld.draw_text(msg, x as i32, y as i32, drawing_details.font_size, color);
-    }
+fn draw_message(game: &Game, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  const MAX_DURATION: f32 = LineClearMessage::DURATION.as_secs_f32();
+  let duration = game.message().remaining_time.as_secs_f32();
+  let alpha = (255.0 * duration / MAX_DURATION) as u8;
+  if let Some(message) = game.message().message {
+    let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 4, drawing_details);
+    let (msg, mut color) = (message.name(), message_color(message));
+    color.a = alpha;
+    rld.draw_text(msg, x as i32, y as i32, drawing_details.font_size, color);
+  }
 
-    if let Some((tetromino, spin_type)) = self.message.spin {
-      let font_size = drawing_details.font_size;
-      let font_size_small = drawing_details.font_size_small;
-      let mut spin_color = tetromino.color();
-      spin_color.a = alpha;
-      let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 6, drawing_details);
-      let spin_text = format!("{}-SPIN", tetromino.name());
-      rld.draw_text(&spin_text, x as i32, y as i32, font_size, spin_color);
-      if spin_type == SpinType::Mini {
-        let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 7, drawing_details);
-        rld.draw_text("MINI", x as i32, y as i32, font_size_small, spin_color);
-      }
+  if let Some((tetromino, spin_type)) = game.message().spin {
+    let font_size = drawing_details.font_size;
+    let font_size_small = drawing_details.font_size_small;
+    let mut spin_color = tetromino_color(tetromino);
+    spin_color.a = alpha;
+    let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 6, drawing_details);
+    let spin_text = format!("{}-SPIN", tetromino.name());
+    rld.draw_text(&spin_text, x as i32, y as i32, font_size, spin_color);
+    if spin_type == SpinType::Mini {
+      let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 7, drawing_details);
+      rld.draw_text("MINI", x as i32, y as i32, font_size_small, spin_color);
     }
   }
+}
 
-  fn draw_combo(&self, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
-    const COMBO_TEXT: &str = "COMBO ";
-    let font_size = drawing_details.font_size;
-    let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 10, drawing_details);
-    let (x, y) = (x as i32, y as i32);
-    let combo = format!("{}", self.combo);
-    let x_offset = rld.measure_text(COMBO_TEXT, drawing_details.font_size);
+fn draw_finesse_fault(
+  finesse: Finesse,
+  remaining_time: Duration,
+  drawing_details: &DrawingDetails,
+  rld: &mut RaylibDrawHandle,
+) {
+  const MAX_DURATION: f32 = Finesse::FAULT_DURATION.as_secs_f32();
+  let mut color = Color::RED;
+  color.a = (255.0 * remaining_time.as_secs_f32() / MAX_DURATION) as u8;
+
+  // Between the spin and the combo, clear of the timers and counters of every mode
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 9, drawing_details);
+  rld.draw_text(
+    "FINESSE",
+    x as i32,
+    y as i32,
+    drawing_details.font_size,
+    color,
+  );
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 8, drawing_details);
+  let extra_keys = format!("+{} KEYS", finesse.extra_keys());
+  rld.draw_text(
+    &extra_keys,
+    x as i32,
+    y as i32,
+    drawing_details.font_size_small,
+    color,
+  );
+}
 
-    rld.draw_text(COMBO_TEXT, x, y, font_size, Color::BLUE);
-    rld.draw_text(&combo, x + x_offset, y, font_size, Color::BLUE);
-  }
+fn draw_combo(game: &Game, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  const COMBO_TEXT: &str = "COMBO ";
+  let font_size = drawing_details.font_size;
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 10, drawing_details);
+  let (x, y) = (x as i32, y as i32);
+  let combo = format!("{}", game.combo());
+  let x_offset = rld.measure_text(COMBO_TEXT, drawing_details.font_size);
 
-  fn draw_b2b(&self, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
-    const B2B_TEXT: &str = "B2B ";
-    let font_size = drawing_details.font_size;
-    let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 12, drawing_details);
-    let (x, y) = (x as i32, y as i32);
-    let b2b = format!("{}", self.b2b - 1);
-    let x_offset = rld.measure_text(B2B_TEXT, drawing_details.font_size);
+  rld.draw_text(COMBO_TEXT, x, y, font_size, Color::BLUE);
+  rld.draw_text(&combo, x + x_offset, y, font_size, Color::BLUE);
+}
 
-    rld.draw_text(B2B_TEXT, x, y, font_size, Color::BLUE);
-    rld.draw_text(&b2b, x + x_offset, y, font_size, Color::BLUE);
-  }
+fn draw_b2b(game: &Game, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  const B2B_TEXT: &str = "B2B ";
+  let font_size = drawing_details.font_size;
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 12, drawing_details);
+  let (x, y) = (x as i32, y as i32);
+  let b2b = format!("{}", game.b2b() - 1);
+  let x_offset = rld.measure_text(B2B_TEXT, drawing_details.font_size);
 
-  fn draw_score(&self, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
-    let font_size = drawing_details.font_size;
-    let Vector2 { x, y } = get_block(WIDTH + 1, 1, drawing_details);
-    let (x, y) = (x as i32, y as i32);
-    let score = format!("{:09}", self.score);
-    let y_offset = (drawing_details.block_length / 2.0) as i32;
+  rld.draw_text(B2B_TEXT, x, y, font_size, Color::BLUE);
+  rld.draw_text(&b2b, x + x_offset, y, font_size, Color::BLUE);
+}
+
+fn draw_garbage_meter(game: &Game, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let lines = game
+    .pending_garbage()
+    .min(drawing_details.visible_height as u32);
+  let Vector2 { x, y } = get_block(0, lines as i32 - 1, drawing_details);
+  let meter = Rectangle {
+    x: x - drawing_details.block_length / 2.0,
+    y,
+    width: drawing_details.block_length / 3.0,
+    height: drawing_details.block_length * lines as f32,
+  };
+  rld.draw_rectangle_rec(meter, DrawingDetails::GARBAGE_METER_COLOR);
+}
 
+fn draw_score(game: &Game, drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let font_size = drawing_details.font_size;
+  let Vector2 { x, y } = get_block(drawing_details.width + 1, 1, drawing_details);
+  let (x, y) = (x as i32, y as i32);
+  let score = format!("{:09}", game.score());
+  let y_offset = (drawing_details.block_length / 2.0) as i32;
+
+  rld.draw_text(
+    &score,
+    x,
+    y + y_offset,
+    font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let progress = format!("LEVEL {}  LINES {}", game.level(), game.lines());
+  rld.draw_text(
+    &progress,
+    x,
+    y + y_offset + font_size,
+    drawing_details.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+}
+
+/// Draws the statistics of `game` in a column right of the next queue.
+pub fn draw_stats(game: &Game, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let stats = game.stats();
+  let mut lines = vec![
+    format!("TIME {}", format_time(stats.time())),
+    format!("PIECES {}", stats.pieces()),
+    format!("LINES {}", game.lines()),
+    format!("PPS {:.2}", stats.pps()),
+    format!("APM {:.1}", stats.apm()),
+    format!("KPP {:.2}", stats.kpp()),
+    format!("FAULTS {}", stats.finesse_faults()),
+    String::new(),
+  ];
+  lines.extend(
+    stats
+      .clears()
+      .iter()
+      .map(|clear| format!("{} {}", clear.count, clear.name)),
+  );
+
+  let Vector2 { x, y } = get_block(
+    d.width + DrawingDetails::RIGHT_BORDER,
+    d.visible_height - 1,
+    d,
+  );
+  let x = (x + d.block_length / 2.0) as i32;
+  for (i, line) in lines.iter().enumerate() {
+    let y = y as i32 + i as i32 * d.font_size_small;
     rld.draw_text(
-      &score,
+      line,
       x,
-      y + y_offset,
-      font_size,
+      y,
+      d.font_size_small,
       DrawingDetails::INFO_TEXT_COLOR,
     );
   }
@@ -199,7 +213,7 @@ impl Game {
 fn get_block(i: i32, j: i32, d: &DrawingDetails) -> Vector2 {
   Vector2 {
     x: d.position.x + i as f32 * d.block_length,
-    y: d.position.y + (VISIBLE_HEIGHT - j - 1) as f32 * d.block_length,
+    y: d.position.y + (d.visible_height - j - 1) as f32 * d.block_length,
   }
 }
 
@@ -209,8 +223,3 @@ pub fn screen_vector(rl: &RaylibHandle) -> Vector2 {
     y: rl.get_screen_height() as f32,
   }
 }
-
-pub const PLAYFIELD_VECTOR: Vector2 = Vector2 {
-  x: WIDTH as f32,
-  y: VISIBLE_HEIGHT as f32,
-};
@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use raylib::{
+  color::Color,
+  math::Vector2,
+  prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use rustris::{
+  game::Game,
+  mode::{
+    Mode, cheese::Cheese, master::Master, pc_training::PcTraining, puzzle::Puzzles, sprint::Sprint,
+    survival::Survival, ultra::Ultra,
+  },
+  stats::Stats,
+};
+
+use super::{DrawingDetails, playfield::get_block};
+
+/// Draws the progress of `mode`, whose pace is taken from the statistics of `game`.
+pub fn draw_mode(mode: &Mode, game: &Game, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let stats = game.stats();
+  match mode {
+    Mode::Marathon | Mode::Classic => {}
+    Mode::Sprint(sprint) => draw_sprint(sprint, stats, d, rld),
+    Mode::Ultra(ultra) => draw_ultra(ultra, d, rld),
+    Mode::Cheese(cheese) => draw_cheese(cheese, stats, d, rld),
+    Mode::Survival(survival) => draw_survival(survival, d, rld),
+    Mode::Master(master) => draw_master(master, d, rld),
+    Mode::Puzzle(puzzles) => draw_puzzle(puzzles, d, rld),
+    Mode::PcTraining(training) => draw_pc_training(training, d, rld),
+  }
+}
+
+/// Formats a time as minutes, seconds and milliseconds.
+pub fn format_time(time: Duration) -> String {
+  let millis = time.as_millis();
+  format!(
+    "{}:{:02}.{:03}",
+    millis / 60_000,
+    millis / 1000 % 60,
+    millis % 1000
+  )
+}
+
+/// How far ahead or behind a split is compared to the personal best, colored accordingly.
+pub fn split_delta(split: Duration, best: Option<Duration>) -> (String, Color) {
+  let Some(best) = best else {
+    return (String::new(), Color::WHITE);
+  };
+
+  if split <= best {
+    let delta = (best - split).as_secs_f32();
+    (format!("-{delta:.2}"), Color::LIME)
+  } else {
+    let delta = (split - best).as_secs_f32();
+    (format!("+{delta:.2}"), Color::RED)
+  }
+}
+
+fn draw_sprint(sprint: &Sprint, stats: &Stats, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  rld.draw_text(
+    &format_time(sprint.time()),
+    x as i32,
+    y as i32,
+    d.font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 0, d);
+  let pace = format!("{:.2} PPS", stats.pps());
+  rld.draw_text(
+    &pace,
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  if !sprint.has_started() {
+    draw_ready(d, rld);
+  }
+}
+
+fn draw_ultra(ultra: &Ultra, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  const HURRY_TIME: Duration = Duration::from_secs(10);
+  let remaining_time = ultra.remaining_time();
+  let color = if remaining_time <= HURRY_TIME {
+    Color::RED
+  } else {
+    DrawingDetails::INFO_TEXT_COLOR
+  };
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  rld.draw_text(
+    &format_time(remaining_time),
+    x as i32,
+    y as i32,
+    d.font_size,
+    color,
+  );
+
+  if !ultra.has_started() {
+    draw_ready(d, rld);
+  }
+}
+
+fn draw_cheese(cheese: &Cheese, stats: &Stats, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  rld.draw_text(
+    &format_time(cheese.time()),
+    x as i32,
+    y as i32,
+    d.font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 0, d);
+  let remaining_lines = cheese.goal().lines() - cheese.cleared_lines();
+  let progress = format!("{remaining_lines} LEFT  {} PIECES", stats.pieces());
+  rld.draw_text(
+    &progress,
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  if !cheese.has_started() {
+    draw_ready(d, rld);
+  }
+}
+
+fn draw_survival(survival: &Survival, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  rld.draw_text(
+    &format_time(survival.time()),
+    x as i32,
+    y as i32,
+    d.font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 0, d);
+  let rise = format!("RISE {:.1}", survival.time_to_rise().as_secs_f32());
+  rld.draw_text(
+    &rise,
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::GARBAGE_METER_COLOR,
+  );
+
+  if !survival.has_started() {
+    draw_ready(d, rld);
+  }
+}
+
+fn draw_master(master: &Master, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  rld.draw_text(
+    &format_time(master.time()),
+    x as i32,
+    y as i32,
+    d.font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 0, d);
+  let sections = master.section_times().len() as u32;
+  let grade = format!("GRADE {}  SECTION {}", master.grade(), sections + 1);
+  rld.draw_text(
+    &grade,
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  if !master.has_started() {
+    draw_ready(d, rld);
+  }
+}
+
+fn draw_puzzle(puzzles: &Puzzles, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let puzzle = puzzles.puzzle();
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  rld.draw_text(
+    &puzzle.name,
+    x as i32,
+    y as i32,
+    d.font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 1, d);
+  rld.draw_text(
+    &puzzle.goal.name(),
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 0, d);
+  let pieces = format!("PIECE {}/{}", puzzles.pieces(), puzzle.piece_limit());
+  rld.draw_text(
+    &pieces,
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+}
+
+fn draw_pc_training(training: &PcTraining, d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 2, d);
+  let streak = format!("{} PC", training.streak());
+  rld.draw_text(
+    &streak,
+    x as i32,
+    y as i32,
+    d.font_size,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+
+  let Vector2 { x, y } = get_block(DrawingDetails::LEFT_BORDER, 0, d);
+  let record = format!(
+    "BEST {}  TOTAL {}  FAILS {}",
+    training.best_streak(),
+    training.perfect_clears(),
+    training.failures()
+  );
+  rld.draw_text(
+    &record,
+    x as i32,
+    y as i32,
+    d.font_size_small,
+    DrawingDetails::INFO_TEXT_COLOR,
+  );
+}
+
+/// Shown over the board until the first input starts the clock.
+fn draw_ready(d: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  const READY_TEXT: &str = "READY";
+  let Vector2 { x, y } = get_block(0, d.visible_height / 2, d);
+  let x_offset =
+    (d.block_length * d.width as f32) as i32 - rld.measure_text(READY_TEXT, d.font_size);
+  rld.draw_text(
+    READY_TEXT,
+    x as i32 + x_offset / 2,
+    y as i32,
+    d.font_size,
+    Color::SKYBLUE,
+  );
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use raylib::{
+  RaylibHandle,
+  color::Color,
+  consts::KeyboardKey,
+  prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use rustris::replay::{Playback, Replay, TICK};
+
+use super::{
+  Draw, DrawingDetails,
+  game::{draw_stats, screen_vector},
+  mode::{draw_mode, format_time},
+};
+
+/// The playback speeds to pick from, as multiples of real time.
+const SPEEDS: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0];
+const NORMAL_SPEED: usize = 3;
+
+pub struct ReplayViewer {
+  playback: Playback,
+  pause: bool,
+  /// An index into [`SPEEDS`].
+  speed: usize,
+  /// Time not yet played because it is shorter than a tick.
+  lag: Duration,
+  drawing_details: DrawingDetails,
+}
+
+impl ReplayViewer {
+  pub fn new(replay: Replay, rl: &RaylibHandle) -> Self {
+    let playback = Playback::new(replay);
+    let screen = screen_vector(rl);
+    let drawing_details =
+      DrawingDetails::new(playback.game().playfield().size(), screen, screen / 2.0);
+    Self {
+      playback,
+      pause: false,
+      speed: NORMAL_SPEED,
+      lag: Duration::ZERO,
+      drawing_details,
+    }
+  }
+
+  pub fn update(&mut self, dt: Duration, rl: &RaylibHandle) {
+    if rl.is_key_pressed(KeyboardKey::KEY_R) {
+      self.playback.restart();
+      self.lag = Duration::ZERO;
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+      self.pause = !self.pause;
+    }
+    if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+      self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    } else if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+      self.speed = self.speed.saturating_sub(1);
+    }
+
+    if self.pause {
+      let tick = self.playback.tick();
+      if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+        self.playback.step();
+      } else if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+        self.playback.seek(tick.saturating_sub(1));
+      }
+      return;
+    }
+
+    self.lag += dt.mul_f32(SPEEDS[self.speed]);
+    while self.lag >= TICK && !self.playback.is_over() {
+      self.lag -= TICK;
+      self.playback.step();
+    }
+    if self.playback.is_over() {
+      self.lag = Duration::ZERO;
+    }
+  }
+
+  pub fn draw(&self, rld: &mut RaylibDrawHandle) {
+    rld.clear_background(DrawingDetails::BACKGROUND_COLOR);
+    self.playback.game().draw(&self.drawing_details, rld);
+    let game = self.playback.game();
+    draw_mode(self.playback.mode(), game, &self.drawing_details, rld);
+    draw_stats(self.playback.game(), &self.drawing_details, rld);
+
+    const REPLAY_COLOR: Color = Color::DARKBLUE;
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+    let state = if self.pause {
+      "PAUSED".to_string()
+    } else {
+      format!("{}x", SPEEDS[self.speed])
+    };
+    let status = format!(
+      "REPLAY  {} / {}  {state}",
+      format_time(self.playback.time()),
+      format_time(self.playback.replay().duration())
+    );
+    rld.draw_text(
+      &status,
+      font_size_small,
+      font_size_small,
+      font_size,
+      REPLAY_COLOR,
+    );
+
+    const CONTROLS_TEXT: &str =
+      "Enter: pause  Up/Down: speed  Left/Right: step while paused  R: restart  Esc: quit";
+    let y = rld.get_render_height() - 2 * font_size_small;
+    rld.draw_text(
+      CONTROLS_TEXT,
+      font_size_small,
+      y,
+      font_size_small,
+      REPLAY_COLOR,
+    );
+  }
+
+  pub fn should_stop_running(&self, rl: &RaylibHandle) -> bool {
+    rl.is_key_pressed(KeyboardKey::KEY_ESCAPE)
+  }
+}
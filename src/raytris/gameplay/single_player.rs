@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use rand::random;
 use raylib::{
   RaylibHandle,
   color::Color,
@@ -7,21 +8,62 @@ use raylib::{
   prelude::{RaylibDraw, RaylibDrawHandle},
 };
 
+use rustris::{
+  HandlingSettings,
+  fumen::{self, FumenError},
+  game::Game,
+  level::MASTER_LAST_LEVEL,
+  mode::{
+    Mode,
+    cheese::Cheese,
+    master::{Master, SECTION_LEVELS},
+    puzzle::Puzzles,
+    sprint::{SPLIT_LINES, Sprint},
+    survival::Survival,
+    ultra::{ClearTally, Ultra},
+  },
+  playfield::Position,
+  replay::{Replay, TICK, Ticker},
+  rules::Rules,
+};
+
 use super::{
-  Controller, DrawingDetails, HandlingSettings,
-  game::{Game, PLAYFIELD_VECTOR, screen_vector},
-  playfield::VISIBLE_HEIGHT,
+  Controller, Draw, DrawingDetails,
+  game::{draw_stats, screen_vector},
+  mode::{draw_mode, format_time, split_delta},
+};
+use crate::raytris::{
+  records::{Records, SprintRecord},
+  replays,
+  saves::{self, SavedGame},
 };
 
 pub struct SinglePlayer {
   game: Game,
+  mode: Mode,
+  records: Records,
+  /// The record to beat, as it was when the current game started.
+  personal_best: Option<SprintRecord>,
   pause: bool,
   drawing_details: DrawingDetails,
   handling_settings: HandlingSettings,
-  undo_stack: Vec<Game>,
+  /// Earlier states of the game, with the number of ticks recorded when they were left.
+  undo_stack: Vec<(Game, u32)>,
+  ticker: Ticker,
+  replay: Replay,
+  /// The save slot the game goes to if left in progress, `None` when every slot was taken.
+  slot: Option<usize>,
+  /// Whether the game was resumed from its save slot, which is emptied once the game is over.
+  is_resumed: bool,
+  /// A short message about the last copy, paste or finesse fault, with how long it stays on screen.
+  notice: Option<(String, Duration)>,
+  /// Whether a finesse fault starts the game over.
+  finesse_trainer: bool,
 }
 
-// TODO: implement saving and loading game state
+/// How long notices stay on screen.
+const NOTICE_TIME: Duration = Duration::from_secs(2);
+
 impl SinglePlayer {
   const KEYBOARD_CONTROLS: Controller = Controller {
     restart: |rl| rl.is_key_pressed(KeyboardKey::KEY_R),
@@ -38,71 +80,362 @@ impl SinglePlayer {
     undo: |rl| {
       rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) && rl.is_key_pressed(KeyboardKey::KEY_Z)
     },
+    copy_board: |rl| {
+      rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+        && !rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT)
+        && rl.is_key_pressed(KeyboardKey::KEY_C)
+    },
+    copy_history: |rl| {
+      rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+        && rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT)
+        && rl.is_key_pressed(KeyboardKey::KEY_C)
+    },
+    paste_board: |rl| {
+      rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) && rl.is_key_pressed(KeyboardKey::KEY_V)
+    },
     pause: |rl| rl.is_key_pressed(KeyboardKey::KEY_ENTER),
     quit: |rl| rl.is_key_pressed(KeyboardKey::KEY_ESCAPE),
   };
 
-  fn drawing_details(rl: &RaylibHandle) -> DrawingDetails {
-    let block_length =
-      DrawingDetails::HEIGHT_SCALE_FACTOR * rl.get_screen_height() as f32 / VISIBLE_HEIGHT as f32;
-    let position = (screen_vector(rl) - PLAYFIELD_VECTOR * block_length) / 2.0;
-    DrawingDetails::new(block_length, position)
+  fn drawing_details(game: &Game, rl: &RaylibHandle) -> DrawingDetails {
+    let screen = screen_vector(rl);
+    DrawingDetails::new(game.playfield().size(), screen, screen / 2.0)
+  }
+
+  /// Starts a game saved to `slot` if left in progress, or to the next empty slot when another game
+  /// is already in it.
+  pub fn new(
+    handling_settings: HandlingSettings,
+    rules: Rules,
+    mode: Mode,
+    slot: usize,
+    finesse_trainer: bool,
+    rl: &RaylibHandle,
+  ) -> Self {
+    let game = Game::new(random(), mode.rules(rules));
+    let replay = Replay::new(&game, &mode, handling_settings);
+    let saved_game = SavedGame {
+      game,
+      mode,
+      handling_settings,
+      replay,
+    };
+    let free_slot = saves::free_slot(slot);
+    let mut single_player =
+      Self::from_saved_game(saved_game, free_slot, false, finesse_trainer, rl);
+    let notice = match free_slot {
+      Some(free_slot) if free_slot == slot => return single_player,
+      Some(free_slot) => format!("SAVING TO SLOT {}", free_slot + 1),
+      None => "SAVE SLOTS FULL, NOT SAVING".to_string(),
+    };
+    single_player.notice = Some((notice, NOTICE_TIME));
+    single_player
   }
 
-  pub fn new(handling_settings: HandlingSettings, rl: &RaylibHandle) -> Self {
-    let game = Game::new();
-    let pause = false;
-    let drawing_details = Self::drawing_details(rl);
-    let undo_stack = vec![game.clone()];
+  /// Starts a Marathon game on `position`, which must be as wide as the board of `rules`.
+  pub fn from_position(
+    handling_settings: HandlingSettings,
+    rules: Rules,
+    position: Position,
+    slot: usize,
+    finesse_trainer: bool,
+    rl: &RaylibHandle,
+  ) -> Self {
+    let mut single_player = Self::new(
+      handling_settings,
+      rules,
+      Mode::Marathon,
+      slot,
+      finesse_trainer,
+      rl,
+    );
+    single_player.set_up(position);
+    single_player
+  }
+
+  /// Picks up the game saved in `slot` where it was left, paused.
+  pub fn resume(
+    saved_game: SavedGame,
+    slot: usize,
+    finesse_trainer: bool,
+    rl: &RaylibHandle,
+  ) -> Self {
+    Self::from_saved_game(saved_game, Some(slot), true, finesse_trainer, rl)
+  }
+
+  fn from_saved_game(
+    saved_game: SavedGame,
+    slot: Option<usize>,
+    is_resumed: bool,
+    finesse_trainer: bool,
+    rl: &RaylibHandle,
+  ) -> Self {
+    let SavedGame {
+      game,
+      mode,
+      handling_settings,
+      replay,
+    } = saved_game;
+    let records = Records::load();
+    let personal_best = Self::personal_best(&mode, &records);
+    let pause = is_resumed;
+    let drawing_details = Self::drawing_details(&game, rl);
+    let undo_stack = vec![(game.clone(), replay.ticks())];
     Self {
       game,
+      mode,
+      records,
+      personal_best,
       pause,
       drawing_details,
       handling_settings,
       undo_stack,
+      ticker: Ticker::new(),
+      replay,
+      slot,
+      is_resumed,
+      notice: None,
+      finesse_trainer,
     }
   }
-  pub fn update(&mut self, dt: Duration, rl: &RaylibHandle) {
+
+  fn personal_best(mode: &Mode, records: &Records) -> Option<SprintRecord> {
+    match mode {
+      Mode::Sprint(sprint) => records.sprint.get(&sprint.goal().lines()).cloned(),
+      _ => None,
+    }
+  }
+
+  /// Keeps the result of a mode that was just finished if it beats the personal best.
+  fn save_record(&mut self) {
+    let Mode::Sprint(sprint) = &self.mode else {
+      return;
+    };
+
+    let record = SprintRecord {
+      time: sprint.time(),
+      splits: sprint.splits().to_vec(),
+    };
+    let best = self.records.sprint.entry(sprint.goal().lines());
+    best
+      .and_modify(|best| {
+        if record.time < best.time {
+          *best = record.clone();
+        }
+      })
+      .or_insert(record);
+    self.records.save();
+  }
+
+  pub fn update(&mut self, dt: Duration, rl: &mut RaylibHandle) {
+    if let Some((_, time)) = &mut self.notice {
+      *time = time.saturating_sub(dt);
+    }
+    self.notice.take_if(|(_, time)| time.is_zero());
+
+    if (Self::KEYBOARD_CONTROLS.copy_board)(rl) {
+      self.copy(&[self.game.playfield().position()], rl);
+      return;
+    }
+    if (Self::KEYBOARD_CONTROLS.copy_history)(rl) {
+      let mut positions: Vec<_> = self
+        .undo_stack
+        .iter()
+        .map(|(game, _)| game.playfield().position())
+        .collect();
+      let position = self.game.playfield().position();
+      if positions.last() != Some(&position) {
+        positions.push(position);
+      }
+      self.copy(&positions, rl);
+      return;
+    }
+    if (Self::KEYBOARD_CONTROLS.paste_board)(rl) {
+      self.paste(rl);
+      return;
+    }
+
     if (Self::KEYBOARD_CONTROLS.undo)(rl)
-      && let Some(top) = self.undo_stack.pop()
+      && self.mode.allows_undo()
+      && let Some((top, ticks)) = self.undo_stack.pop()
     {
       self.game = top;
+      self.replay.truncate(ticks);
       if self.undo_stack.is_empty() {
-        self.undo_stack.push(self.game.clone());
+        self.undo_stack.push((self.game.clone(), ticks));
       }
       return;
     }
 
     if (Self::KEYBOARD_CONTROLS.restart)(rl) {
-      self.game.reset();
+      self.restart();
+    }
+
+    if let Mode::Puzzle(puzzles) = &mut self.mode
+      && puzzles.is_finished()
+    {
+      if (Self::KEYBOARD_CONTROLS.left)(rl) {
+        puzzles.prev_puzzle();
+        self.new_puzzle_game(rl);
+      } else if (Self::KEYBOARD_CONTROLS.right)(rl) {
+        puzzles.next_puzzle();
+        self.new_puzzle_game(rl);
+      }
     }
 
     if (Self::KEYBOARD_CONTROLS.pause)(rl) {
       self.pause = !self.pause;
     }
 
-    if !self.pause
-      && self
-        .game
-        .update(dt, &Self::KEYBOARD_CONTROLS, &self.handling_settings, rl)
-    {
-      self.undo_stack.push(self.game.clone());
+    if self.pause {
+      return;
+    }
+
+    let was_finished = self.mode.is_finished();
+    let input = Self::KEYBOARD_CONTROLS.input(rl);
+    for input in self.ticker.ticks(dt, input) {
+      if self.game.has_lost() || self.mode.is_finished() {
+        break;
+      }
+
+      self.replay.record(input);
+      let finesse_faults = self.game.stats().finesse_faults();
+      if self
+        .mode
+        .update(&mut self.game, TICK, input, &self.handling_settings)
+      {
+        if self.finesse_trainer && self.game.stats().finesse_faults() > finesse_faults {
+          self.restart();
+          self.notice = Some(("FINESSE FAULT".to_string(), NOTICE_TIME));
+          break;
+        }
+        self
+          .undo_stack
+          .push((self.game.clone(), self.replay.ticks()));
+      }
+    }
+    if !was_finished && self.mode.is_finished() {
+      self.save_record();
     }
   }
 
+  fn restart(&mut self) {
+    // A game set up from a position starts over from it
+    if let Some(position) = self.replay.position.clone() {
+      self.set_up(position);
+    } else {
+      self.game.reset(random());
+      self.mode.reset();
+      self.undo_stack = vec![(self.game.clone(), 0)];
+      self.start_recording();
+    }
+    self.personal_best = Self::personal_best(&self.mode, &self.records);
+  }
+
+  /// Puts `positions` on the clipboard as a fumen.
+  fn copy(&mut self, positions: &[Position], rl: &mut RaylibHandle) {
+    let copied = fumen::encode(positions)
+      .map_err(|error| error.to_string())
+      .and_then(|fumen| {
+        rl.set_clipboard_text(&fumen)
+          .map_err(|error| error.to_string())
+      });
+    let notice = match copied {
+      Ok(()) if positions.len() > 1 => format!("COPIED {} PAGES", positions.len()),
+      Ok(()) => "COPIED BOARD".to_string(),
+      Err(error) => error,
+    };
+    self.notice = Some((notice, NOTICE_TIME));
+  }
+
+  /// Starts a new game on the board of the fumen on the clipboard. Only modes that allow taking
+  /// moves back can be played from any board.
+  fn paste(&mut self, rl: &RaylibHandle) {
+    if !self.mode.allows_undo() {
+      return;
+    }
+
+    let width = self.game.playfield().size().width as usize;
+    let pasted = rl
+      .get_clipboard_text()
+      .map_err(|error| error.to_string())
+      .and_then(|text| fumen::decode(&text).map_err(|error| error.to_string()))
+      .and_then(|position| match width {
+        10 => Ok(position),
+        _ => Err(FumenError::Width(width).to_string()),
+      });
+    let position = match pasted {
+      Ok(position) => position,
+      Err(error) => {
+        self.notice = Some((error, NOTICE_TIME));
+        return;
+      }
+    };
+
+    self.set_up(position);
+    self.notice = Some(("PASTED BOARD".to_string(), NOTICE_TIME));
+  }
+
+  /// Starts the game over from `position` instead of an empty board.
+  fn set_up(&mut self, position: Position) {
+    self.game.reset(random());
+    self.mode.reset();
+    self
+      .game
+      .set_up(&position.rows, &position.pieces, position.hold);
+    self.undo_stack = vec![(self.game.clone(), 0)];
+    self.start_recording();
+    self.replay.position = Some(position);
+  }
+
+  /// Keeps the recording of the last game and starts one for the current game.
+  fn start_recording(&mut self) {
+    if self.replay.ticks() > 0 {
+      replays::save(&self.replay);
+    }
+    self.ticker = Ticker::new();
+    self.replay = Replay::new(&self.game, &self.mode, self.handling_settings);
+  }
+
+  /// Starts a game on the board of the current puzzle, which may not be as large as the last one.
+  fn new_puzzle_game(&mut self, rl: &RaylibHandle) {
+    let rules = self.mode.rules(*self.game.playfield().rules());
+    self.game = Game::new(random(), rules);
+    self.drawing_details = Self::drawing_details(&self.game, rl);
+    self.undo_stack = vec![(self.game.clone(), 0)];
+    self.start_recording();
+  }
+
   pub fn draw(&self, rld: &mut RaylibDrawHandle) {
     rld.clear_background(DrawingDetails::BACKGROUND_COLOR);
     self.game.draw(&self.drawing_details, rld);
+    draw_mode(&self.mode, &self.game, &self.drawing_details, rld);
+    draw_stats(&self.game, &self.drawing_details, rld);
+
+    if let Some((notice, _)) = &self.notice {
+      const NOTICE_COLOR: Color = Color::SKYBLUE;
+      let font_size_small = self.drawing_details.font_size_small;
+      rld.draw_text(
+        notice,
+        font_size_small,
+        font_size_small,
+        font_size_small,
+        NOTICE_COLOR,
+      );
+    }
 
     if self.pause {
       self.draw_pause(rld);
+    } else if self.mode.is_finished() {
+      self.draw_finished(rld);
     } else if self.game.has_lost() {
       self.draw_lost(rld);
     }
   }
 
   pub fn should_stop_running(&self, rl: &RaylibHandle) -> bool {
-    (Self::KEYBOARD_CONTROLS.quit)(rl) && (self.pause || self.game.has_lost())
+    let is_over = self.game.has_lost() || self.mode.is_finished();
+    (Self::KEYBOARD_CONTROLS.quit)(rl) && (self.pause || is_over)
   }
 
   fn draw_lost(&self, rld: &mut RaylibDrawHandle) {
@@ -124,6 +457,198 @@ impl SinglePlayer {
     );
 
     self.draw_quit(rld);
+    let font_size = self.drawing_details.font_size;
+    let mut y = half_height + font_size_big + 3 * font_size;
+    self.draw_stats_results(true, &mut y, rld);
+  }
+
+  /// Draws the pace of the game, and the clears made in it if `show_clears` is set.
+  fn draw_stats_results(&self, show_clears: bool, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size_small = self.drawing_details.font_size_small;
+    let stats = self.game.stats();
+
+    let totals = format!(
+      "{}  {} PIECES  {} LINES",
+      format_time(stats.time()),
+      stats.pieces(),
+      self.game.lines()
+    );
+    draw_centered(rld, y, &totals, font_size_small, RESULTS_COLOR);
+    let pace = format!(
+      "{:.2} PPS  {:.1} APM  {:.2} KPP  {} FAULTS",
+      stats.pps(),
+      stats.apm(),
+      stats.kpp(),
+      stats.finesse_faults()
+    );
+    draw_centered(rld, y, &pace, font_size_small, RESULTS_COLOR);
+
+    if !show_clears {
+      return;
+    }
+    const CLEARS_PER_LINE: usize = 3;
+    for clears in stats.clears().chunks(CLEARS_PER_LINE) {
+      let text: Vec<_> = clears
+        .iter()
+        .map(|clear| format!("{} x{}", clear.name, clear.count))
+        .collect();
+      draw_centered(rld, y, &text.join("  "), font_size_small, RESULTS_COLOR);
+    }
+  }
+
+  fn draw_finished(&self, rld: &mut RaylibDrawHandle) {
+    let (width, height) = (rld.get_screen_width(), rld.get_render_height());
+    let font_size_big = self.drawing_details.font_size_big;
+    let font_size_small = self.drawing_details.font_size_small;
+    let mut y = height / 10;
+
+    const FINISHED_COLOR: Color = Color::GREEN;
+    let title = match &self.mode {
+      Mode::Ultra(_) => "TIME UP",
+      Mode::Survival(_) => "TOPPED OUT",
+      Mode::Puzzle(puzzles) if puzzles.outcome() == Some(false) => "FAILED",
+      Mode::Puzzle(_) => "SOLVED",
+      _ => "FINISHED",
+    };
+    rld.draw_rectangle(0, 0, width, height, DrawingDetails::DARKEN_COLOR);
+    draw_centered(rld, &mut y, title, font_size_big, FINISHED_COLOR);
+
+    match &self.mode {
+      Mode::Marathon | Mode::Classic | Mode::PcTraining(_) => {}
+      Mode::Sprint(sprint) => self.draw_sprint_results(sprint, &mut y, rld),
+      Mode::Ultra(ultra) => self.draw_ultra_results(ultra, &mut y, rld),
+      Mode::Cheese(cheese) => self.draw_cheese_results(cheese, &mut y, rld),
+      Mode::Survival(survival) => self.draw_survival_results(survival, &mut y, rld),
+      Mode::Master(master) => self.draw_master_results(master, &mut y, rld),
+      Mode::Puzzle(puzzles) => self.draw_puzzle_results(puzzles, &mut y, rld),
+    }
+    y += font_size_small;
+    // Ultra results already list every clear, with its score
+    let show_clears = !matches!(self.mode, Mode::Ultra(_));
+    self.draw_stats_results(show_clears, &mut y, rld);
+    y += font_size_small;
+
+    draw_centered(
+      rld,
+      &mut y,
+      "Press Esc to quit",
+      font_size_small,
+      RESULTS_COLOR,
+    );
+  }
+
+  fn draw_sprint_results(&self, sprint: &Sprint, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+
+    let is_new_best = self
+      .personal_best
+      .as_ref()
+      .is_none_or(|best| sprint.time() < best.time);
+    let time_color = if is_new_best {
+      Color::GOLD
+    } else {
+      RESULTS_COLOR
+    };
+    draw_centered(rld, y, &format_time(sprint.time()), font_size, time_color);
+    if is_new_best {
+      draw_centered(rld, y, "NEW BEST", font_size_small, Color::GOLD);
+    }
+    let stats = self.game.stats();
+    let pace = format!("{} PIECES  {:.2} PPS", stats.pieces(), stats.pps());
+    draw_centered(rld, y, &pace, font_size_small, RESULTS_COLOR);
+    *y += font_size_small;
+
+    let best_splits = self.personal_best.as_ref().map(|best| &best.splits);
+    for (i, &split) in sprint.splits().iter().enumerate() {
+      let lines = (i as u32 + 1) * SPLIT_LINES;
+      let best_split = best_splits.and_then(|splits| splits.get(i)).copied();
+      let (delta, color) = split_delta(split, best_split);
+      let text = format!("{lines:>3}  {}  {delta}", format_time(split));
+      draw_centered(rld, y, &text, font_size_small, color);
+    }
+  }
+
+  fn draw_ultra_results(&self, ultra: &Ultra, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+
+    let score = format!("{:09}", self.game.score());
+    draw_centered(rld, y, &score, font_size, Color::GOLD);
+    let lines = format!("{} LINES", self.game.lines());
+    draw_centered(rld, y, &lines, font_size_small, RESULTS_COLOR);
+    *y += font_size_small;
+
+    for ClearTally { name, count, score } in ultra.clears() {
+      let text = format!("{name} x{count}  {score}");
+      draw_centered(rld, y, &text, font_size_small, RESULTS_COLOR);
+    }
+  }
+
+  fn draw_cheese_results(&self, cheese: &Cheese, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+
+    draw_centered(rld, y, &format_time(cheese.time()), font_size, Color::GOLD);
+    let lines = format!("{} GARBAGE LINES", cheese.cleared_lines());
+    draw_centered(rld, y, &lines, font_size_small, RESULTS_COLOR);
+    let stats = self.game.stats();
+    let pace = format!("{} PIECES  {:.2} PPS", stats.pieces(), stats.pps());
+    draw_centered(rld, y, &pace, font_size_small, RESULTS_COLOR);
+  }
+
+  fn draw_survival_results(&self, survival: &Survival, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+
+    let time = format_time(survival.time());
+    draw_centered(rld, y, &time, font_size, Color::GOLD);
+    let lines = format!("{} LINES", self.game.lines());
+    draw_centered(rld, y, &lines, font_size_small, RESULTS_COLOR);
+  }
+
+  fn draw_master_results(&self, master: &Master, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+
+    let grade = format!("GRADE {}", master.grade());
+    draw_centered(rld, y, &grade, font_size, Color::GOLD);
+    let result = format!(
+      "{}  LEVEL {}  {}",
+      self.game.score(),
+      self.game.level(),
+      format_time(master.time())
+    );
+    draw_centered(rld, y, &result, font_size_small, RESULTS_COLOR);
+    *y += font_size_small;
+
+    for (i, &time) in master.section_times().iter().enumerate() {
+      let level = ((i as u32 + 1) * SECTION_LEVELS).min(MASTER_LAST_LEVEL);
+      let text = format!("{level:>3}  {}", format_time(time));
+      draw_centered(rld, y, &text, font_size_small, RESULTS_COLOR);
+    }
+  }
+
+  fn draw_puzzle_results(&self, puzzles: &Puzzles, y: &mut i32, rld: &mut RaylibDrawHandle) {
+    let font_size = self.drawing_details.font_size;
+    let font_size_small = self.drawing_details.font_size_small;
+
+    let puzzle = puzzles.puzzle();
+    draw_centered(rld, y, &puzzle.name, font_size, Color::GOLD);
+    draw_centered(rld, y, &puzzle.goal.name(), font_size_small, RESULTS_COLOR);
+    let result = format!(
+      "{} PIECES  {}",
+      puzzles.pieces(),
+      format_time(puzzles.time())
+    );
+    draw_centered(rld, y, &result, font_size_small, RESULTS_COLOR);
+    *y += font_size_small;
+
+    let (index, count) = puzzles.progress();
+    let controls = format!("Puzzle {} of {count}", index + 1);
+    draw_centered(rld, y, &controls, font_size_small, RESULTS_COLOR);
+    let controls = "Press R to retry, Left or Right for another puzzle";
+    draw_centered(rld, y, controls, font_size_small, RESULTS_COLOR);
   }
 
   fn draw_pause(&self, rld: &mut RaylibDrawHandle) {
@@ -164,5 +689,55 @@ impl SinglePlayer {
       font_size,
       QUIT_COLOR,
     );
+
+    let seed_text = format!("Seed {}", self.game.seed());
+    let x_offset = -rld.measure_text(&seed_text, font_size) / 2;
+    rld.draw_text(
+      &seed_text,
+      half_width + x_offset,
+      half_height + y_offset + font_size,
+      font_size,
+      QUIT_COLOR,
+    );
   }
 }
+
+impl Drop for SinglePlayer {
+  fn drop(&mut self) {
+    if self.replay.ticks() > 0 {
+      replays::save(&self.replay);
+    }
+
+    let Some(slot) = self.slot else {
+      return;
+    };
+    let is_over = self.game.has_lost() || self.mode.is_finished();
+    if !is_over && self.replay.ticks() > 0 {
+      let saved_game = SavedGame {
+        game: self.game.clone(),
+        mode: self.mode.clone(),
+        handling_settings: self.handling_settings,
+        replay: self.replay.clone(),
+      };
+      saves::save(slot, &saved_game);
+    } else if is_over && self.is_resumed {
+      saves::clear(slot);
+    }
+  }
+}
+
+const RESULTS_COLOR: Color = Color::WHITE;
+
+/// Draws `text` centered horizontally at `y`, then moves `y` below it.
+fn draw_centered(
+  rld: &mut RaylibDrawHandle,
+  y: &mut i32,
+  text: &str,
+  font_size: i32,
+  color: Color,
+) {
+  let x_offset = -rld.measure_text(text, font_size) / 2;
+  let half_width = rld.get_screen_width() / 2;
+  rld.draw_text(text, half_width + x_offset, *y, font_size, color);
+  *y += font_size;
+}
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use rand::random;
 use raylib::{
   RaylibHandle,
   color::Color,
@@ -8,11 +9,9 @@ use raylib::{
   prelude::{RaylibDraw, RaylibDrawHandle},
 };
 
-use super::{
-  Controller, DrawingDetails, HandlingSettings,
-  game::{Game, PLAYFIELD_VECTOR, screen_vector},
-  playfield::VISIBLE_HEIGHT,
-};
+use rustris::{HandlingSettings, game::Game, rules::Rules};
+
+use super::{Controller, Draw, DrawingDetails, game::screen_vector};
 
 pub struct TwoPlayer {
   games: [(Game, DrawingDetails, HandlingSettings); 2],
@@ -33,6 +32,9 @@ impl TwoPlayer {
     hard_drop: |rl| rl.is_key_pressed(KeyboardKey::KEY_Z),
     soft_drop: |rl| rl.is_key_down(KeyboardKey::KEY_S),
     undo: |_| false,
+    copy_board: |_| false,
+    copy_history: |_| false,
+    paste_board: |_| false,
     pause: |rl| rl.is_key_pressed(KeyboardKey::KEY_ENTER),
     quit: |rl| rl.is_key_pressed(KeyboardKey::KEY_ESCAPE),
   };
@@ -50,31 +52,45 @@ impl TwoPlayer {
     hard_drop: |rl| rl.is_key_pressed(KeyboardKey::KEY_M),
     soft_drop: |rl| rl.is_key_down(KeyboardKey::KEY_K),
     undo: |_| false,
+    copy_board: |_| false,
+    copy_history: |_| false,
+    paste_board: |_| false,
     pause: |rl| rl.is_key_pressed(KeyboardKey::KEY_ENTER),
     quit: |rl| rl.is_key_pressed(KeyboardKey::KEY_ESCAPE),
   };
 
-  fn drawing_details0(rl: &RaylibHandle) -> DrawingDetails {
-    let block_length = DrawingDetails::HEIGHT_SCALE_FACTOR * 0.75 * rl.get_screen_height() as f32
-      / VISIBLE_HEIGHT as f32;
-    let position =
-      (screen_vector(rl) * Vector2 { x: 0.5, y: 1.0 } - PLAYFIELD_VECTOR * block_length) / 2.0;
-    DrawingDetails::new(block_length, position)
+  fn drawing_details0(rules: &Rules, rl: &RaylibHandle) -> DrawingDetails {
+    let area = screen_vector(rl) * Vector2 { x: 0.5, y: 0.75 };
+    let center = screen_vector(rl) * Vector2 { x: 0.25, y: 0.5 };
+    DrawingDetails::new(rules.board, area, center)
   }
 
-  fn drawing_details1(rl: &RaylibHandle) -> DrawingDetails {
-    let block_length = DrawingDetails::HEIGHT_SCALE_FACTOR * 0.75 * rl.get_screen_height() as f32
-      / VISIBLE_HEIGHT as f32;
-    let position =
-      (screen_vector(rl) * Vector2 { x: 1.5, y: 1.0 } - PLAYFIELD_VECTOR * block_length) / 2.0;
-    DrawingDetails::new(block_length, position)
+  fn drawing_details1(rules: &Rules, rl: &RaylibHandle) -> DrawingDetails {
+    let area = screen_vector(rl) * Vector2 { x: 0.5, y: 0.75 };
+    let center = screen_vector(rl) * Vector2 { x: 0.75, y: 0.5 };
+    DrawingDetails::new(rules.board, area, center)
   }
 
-  pub fn new(settings1: HandlingSettings, settings2: HandlingSettings, rl: &RaylibHandle) -> Self {
+  pub fn new(
+    settings1: HandlingSettings,
+    settings2: HandlingSettings,
+    rules: Rules,
+    rl: &RaylibHandle,
+  ) -> Self {
+    // Both players race on the same piece sequence
+    let seed = random();
     Self {
       games: [
-        (Game::new(), Self::drawing_details0(rl), settings1),
-        (Game::new(), Self::drawing_details1(rl), settings2),
+        (
+          Game::new(seed, rules),
+          Self::drawing_details0(&rules, rl),
+          settings1,
+        ),
+        (
+          Game::new(seed, rules),
+          Self::drawing_details1(&rules, rl),
+          settings2,
+        ),
       ],
       pause: false,
     }
@@ -87,8 +103,12 @@ impl TwoPlayer {
 
     if !self.pause {
       let [(game0, _, hand_set0), (game1, _, hand_set1)] = &mut self.games;
-      game0.update(dt, &Self::CONTROLS0, hand_set0, rl);
-      game1.update(dt, &Self::CONTROLS1, hand_set1, rl);
+      game0.update(dt, Self::CONTROLS0.input(rl), hand_set0);
+      game1.update(dt, Self::CONTROLS1.input(rl), hand_set1);
+
+      let (sent0, sent1) = (game0.take_outgoing_garbage(), game1.take_outgoing_garbage());
+      game0.receive_garbage(sent1);
+      game1.receive_garbage(sent0);
     }
   }
 
@@ -99,7 +119,7 @@ impl TwoPlayer {
     game1.draw(drdet1, rld);
 
     if self.pause {
-      Self::draw_pause(drdet0, rld);
+      Self::draw_pause(drdet0, game0.seed(), rld);
     } else {
       if game0.has_lost() {
         self.draw_lost0(rld);
@@ -116,7 +136,7 @@ impl TwoPlayer {
     (Self::CONTROLS0.quit)(rl) && (self.pause || has_lost)
   }
 
-  fn draw_pause(drawing_details: &DrawingDetails, rld: &mut RaylibDrawHandle) {
+  fn draw_pause(drawing_details: &DrawingDetails, seed: u64, rld: &mut RaylibDrawHandle) {
     let (width, height) = (rld.get_screen_width(), rld.get_render_height());
     let (half_width, half_height) = (width / 2, height / 2);
     let font_size = drawing_details.font_size;
@@ -147,6 +167,16 @@ impl TwoPlayer {
       font_size,
       QUIT_COLOR,
     );
+
+    let seed_text = format!("Seed {seed}");
+    let x_offset = -rld.measure_text(&seed_text, font_size) / 2;
+    rld.draw_text(
+      &seed_text,
+      half_width + x_offset,
+      half_height + y_offset + font_size,
+      font_size,
+      QUIT_COLOR,
+    );
   }
 
   fn draw_lost0(&self, rld: &mut RaylibDrawHandle) {
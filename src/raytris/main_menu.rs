@@ -6,17 +6,28 @@ use raylib::{
   prelude::{RaylibDraw, RaylibDrawHandle},
 };
 
+use super::{
+  saves::{self, SAVE_SLOTS},
+  settings::{config, config_mut},
+};
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Option {
   SinglePlayer,
+  Resume,
   TwoPlayer,
+  Replay,
+  Editor,
   Settings,
   Exit,
 }
 
-const OPTIONS: [Option; 4] = [
+const OPTIONS: [Option; 7] = [
   Option::SinglePlayer,
+  Option::Resume,
   Option::TwoPlayer,
+  Option::Replay,
+  Option::Editor,
   Option::Settings,
   Option::Exit,
 ];
@@ -24,7 +35,10 @@ impl Option {
   fn name(self) -> &'static str {
     match self {
       Self::SinglePlayer => "Single Player",
+      Self::Resume => "Resume",
       Self::TwoPlayer => "Two Player",
+      Self::Replay => "Watch Replay",
+      Self::Editor => "Board Editor",
       Self::Settings => "Settings",
       Self::Exit => "Exit",
     }
@@ -32,8 +46,11 @@ impl Option {
 
   fn next(self) -> Self {
     match self {
-      Self::SinglePlayer => Self::TwoPlayer,
-      Self::TwoPlayer => Self::Settings,
+      Self::SinglePlayer => Self::Resume,
+      Self::Resume => Self::TwoPlayer,
+      Self::TwoPlayer => Self::Replay,
+      Self::Replay => Self::Editor,
+      Self::Editor => Self::Settings,
       Self::Settings => Self::Exit,
       Self::Exit => Self::SinglePlayer,
     }
@@ -42,8 +59,11 @@ impl Option {
   fn prev(self) -> Self {
     match self {
       Self::SinglePlayer => Self::Exit,
-      Self::TwoPlayer => Self::SinglePlayer,
-      Self::Settings => Self::TwoPlayer,
+      Self::Resume => Self::SinglePlayer,
+      Self::TwoPlayer => Self::Resume,
+      Self::Replay => Self::TwoPlayer,
+      Self::Editor => Self::Replay,
+      Self::Settings => Self::Editor,
       Self::Exit => Self::Settings,
     }
   }
@@ -51,19 +71,22 @@ impl Option {
 
 pub struct MainMenu {
   selected_option: Option,
+  /// Whether each save slot holds a game, as it was when the menu opened.
+  saved_slots: [bool; SAVE_SLOTS],
 }
 
 impl MainMenu {
   pub fn new() -> Self {
     MainMenu {
       selected_option: Option::SinglePlayer,
+      saved_slots: std::array::from_fn(saves::exists),
     }
   }
 
   pub fn draw(&self, rld: &mut RaylibDrawHandle) {
     let screen_width = rld.get_screen_width() as f32;
     let screen_height = rld.get_screen_height() as f32;
-    let font_size = screen_height / 12.0;
+    let font_size = screen_height / 21.0;
     let font_size_big = screen_height / 4.0;
 
     rld.clear_background(Color::LIGHTGRAY);
@@ -79,7 +102,23 @@ impl MainMenu {
     let separation = 1.5 * font_size;
     let box_height = 1.3 * font_size;
     for (idx, &option) in OPTIONS.iter().enumerate() {
-      let name = option.name();
+      let choice;
+      let name = match option {
+        Option::SinglePlayer => {
+          choice = format!("< {} >", config().mode.name());
+          &choice
+        }
+        Option::Resume => {
+          let slot = config().save_slot;
+          choice = if self.saved_slots.get(slot) == Some(&true) {
+            format!("< {} Slot {} >", option.name(), slot + 1)
+          } else {
+            format!("< Slot {} Empty >", slot + 1)
+          };
+          &choice
+        }
+        _ => option.name(),
+      };
       let is_selected = option == self.selected_option;
       let enclosing_box = Rectangle {
         x: (screen_width - box_width) / 2.0,
@@ -106,6 +145,22 @@ impl MainMenu {
     } else if rl.is_key_pressed(KeyboardKey::KEY_UP) {
       self.selected_option = self.selected_option.prev();
     }
+
+    if self.selected_option == Option::SinglePlayer {
+      let mode = &mut config_mut().mode;
+      if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+        *mode = mode.prev();
+      } else if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+        *mode = mode.next();
+      }
+    } else if self.selected_option == Option::Resume {
+      let slot = &mut config_mut().save_slot;
+      if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+        *slot = (*slot + SAVE_SLOTS - 1) % SAVE_SLOTS;
+      } else if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+        *slot = (*slot + 1) % SAVE_SLOTS;
+      }
+    }
   }
 
   pub fn should_stop_running(&self, rl: &RaylibHandle) -> bool {
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  fs::{read_to_string, write},
+  time::Duration,
+};
+
+const RECORDS_FILE_NAME: &str = "records.raytris";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SprintRecord {
+  pub time: Duration,
+  pub splits: Vec<Duration>,
+}
+
+/// Personal bests, kept next to the settings file.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Records {
+  /// The best sprint for every goal, by number of lines.
+  pub sprint: BTreeMap<u32, SprintRecord>,
+}
+
+impl Records {
+  pub fn load() -> Self {
+    read_to_string(RECORDS_FILE_NAME)
+      .ok()
+      .and_then(|text| serde_json::from_str(&text).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self) {
+    let Ok(serialized) = serde_json::to_string(self) else {
+      return;
+    };
+
+    write(RECORDS_FILE_NAME, serialized).expect("Error writing");
+  }
+}
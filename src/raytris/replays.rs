@@ -0,0 +1,28 @@
+use std::fs::{read_to_string, write};
+
+use rustris::replay::Replay;
+
+const REPLAY_FILE_NAME: &str = "replay.raytris";
+
+/// The last game played, if there is one that can be read.
+pub fn load() -> Option<Replay> {
+  let text = read_to_string(REPLAY_FILE_NAME).ok()?;
+  match serde_json::from_str(&text) {
+    Ok(replay) => Some(replay),
+    Err(error) => {
+      eprintln!("{REPLAY_FILE_NAME}: {error}");
+      None
+    }
+  }
+}
+
+/// Keeps `replay` as the last game played, in place of the one before.
+pub fn save(replay: &Replay) {
+  let Ok(serialized) = serde_json::to_string(replay) else {
+    return;
+  };
+
+  if let Err(error) = write(REPLAY_FILE_NAME, serialized) {
+    eprintln!("{REPLAY_FILE_NAME}: {error}");
+  }
+}
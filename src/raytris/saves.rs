@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::{
+  fs::{read_to_string, remove_file, write},
+  path::Path,
+};
+
+use rustris::{HandlingSettings, game::Game, mode::Mode, replay::Replay};
+
+pub const SAVE_SLOTS: usize = 3;
+
+/// A single player game left in progress, with the recording of it so far.
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+  pub game: Game,
+  pub mode: Mode,
+  pub handling_settings: HandlingSettings,
+  pub replay: Replay,
+}
+
+fn file_name(slot: usize) -> String {
+  format!("save{}.raytris", slot + 1)
+}
+
+pub fn exists(slot: usize) -> bool {
+  Path::new(&file_name(slot)).exists()
+}
+
+/// The first empty slot from `slot` on, going around to the ones before it.
+pub fn free_slot(slot: usize) -> Option<usize> {
+  (0..SAVE_SLOTS)
+    .map(|i| (slot + i) % SAVE_SLOTS)
+    .find(|&slot| !exists(slot))
+}
+
+pub fn load(slot: usize) -> Option<SavedGame> {
+  let file_name = file_name(slot);
+  let text = read_to_string(&file_name).ok()?;
+  match serde_json::from_str(&text) {
+    Ok(saved_game) => Some(saved_game),
+    Err(error) => {
+      eprintln!("{file_name}: {error}");
+      None
+    }
+  }
+}
+
+pub fn save(slot: usize, saved_game: &SavedGame) {
+  let Ok(serialized) = serde_json::to_string(saved_game) else {
+    return;
+  };
+
+  let file_name = file_name(slot);
+  if let Err(error) = write(&file_name, serialized) {
+    eprintln!("{file_name}: {error}");
+  }
+}
+
+/// Empties `slot`, once the game in it is over.
+pub fn clear(slot: usize) {
+  let _ = remove_file(file_name(slot));
+}
@@ -13,7 +13,14 @@ use std::{
   time::Duration,
 };
 
-use super::gameplay::HandlingSettings;
+use rustris::{
+  HandlingSettings,
+  mode::{
+    ModeKind, cheese::CheeseGoal, pc_training::PcStart, sprint::SprintGoal, ultra::UltraLimit,
+  },
+  playfield::BoardSize,
+  rules::Rules,
+};
 
 pub struct SettingsMenu {
   selected_option: Option,
@@ -59,9 +66,21 @@ impl Resolution {
 }
 
 #[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
   pub resolution: Resolution,
   pub handling_settings: HandlingSettings,
+  pub rules: Rules,
+  /// The single player mode last picked in the main menu.
+  pub mode: ModeKind,
+  /// The save slot last picked in the main menu.
+  pub save_slot: usize,
+  pub sprint_goal: SprintGoal,
+  pub ultra_limit: UltraLimit,
+  pub cheese_goal: CheeseGoal,
+  pub pc_start: PcStart,
+  /// Whether single player games start over on a finesse fault.
+  pub finesse_trainer: bool,
 }
 
 const SETTINGS_FILE_NAME: &str = "settings.raytris";
@@ -79,32 +98,99 @@ pub fn config() -> RwLockReadGuard<'static, Config> {
   CONFIG.read().expect("Lock poisoned")
 }
 
-fn config_mut() -> RwLockWriteGuard<'static, Config> {
+pub fn config_mut() -> RwLockWriteGuard<'static, Config> {
   CONFIG.write().expect("Lock poisoned")
 }
 
-const OPTIONS: [Option; 3] = [Option::Resolution, Option::Das, Option::SoftDrop];
+const OPTIONS: [Option; 14] = [
+  Option::Resolution,
+  Option::Das,
+  Option::Arr,
+  Option::SoftDrop,
+  Option::Randomizer,
+  Option::Rotation,
+  Option::Spins,
+  Option::Messiness,
+  Option::Board,
+  Option::Sprint,
+  Option::Ultra,
+  Option::Cheese,
+  Option::PcStart,
+  Option::FinesseTrainer,
+];
+
+const BOARD_SIZES: [BoardSize; 4] = [
+  BoardSize {
+    width: 10,
+    visible_height: 20,
+  },
+  BoardSize {
+    width: 4,
+    visible_height: 20,
+  },
+  BoardSize {
+    width: 12,
+    visible_height: 20,
+  },
+  BoardSize {
+    width: 10,
+    visible_height: 40,
+  },
+];
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Option {
   Resolution,
   Das,
+  Arr,
   SoftDrop,
+  Randomizer,
+  Rotation,
+  Spins,
+  Messiness,
+  Board,
+  Sprint,
+  Ultra,
+  Cheese,
+  PcStart,
+  FinesseTrainer,
 }
 
 impl Option {
   fn next(self) -> Self {
     match self {
       Self::Resolution => Self::Das,
-      Self::Das => Option::SoftDrop,
-      Option::SoftDrop => Self::Resolution,
+      Self::Das => Self::Arr,
+      Self::Arr => Option::SoftDrop,
+      Option::SoftDrop => Self::Randomizer,
+      Self::Randomizer => Self::Rotation,
+      Self::Rotation => Self::Spins,
+      Self::Spins => Self::Messiness,
+      Self::Messiness => Self::Board,
+      Self::Board => Self::Sprint,
+      Self::Sprint => Self::Ultra,
+      Self::Ultra => Self::Cheese,
+      Self::Cheese => Self::PcStart,
+      Self::PcStart => Self::FinesseTrainer,
+      Self::FinesseTrainer => Self::Resolution,
     }
   }
   fn prev(self) -> Self {
     match self {
-      Self::Resolution => Option::SoftDrop,
+      Self::Resolution => Self::FinesseTrainer,
       Self::Das => Self::Resolution,
-      Option::SoftDrop => Self::Das,
+      Self::Arr => Self::Das,
+      Option::SoftDrop => Self::Arr,
+      Self::Randomizer => Option::SoftDrop,
+      Self::Rotation => Self::Randomizer,
+      Self::Spins => Self::Rotation,
+      Self::Messiness => Self::Spins,
+      Self::Board => Self::Messiness,
+      Self::Sprint => Self::Board,
+      Self::Ultra => Self::Sprint,
+      Self::Cheese => Self::Ultra,
+      Self::PcStart => Self::Cheese,
+      Self::FinesseTrainer => Self::PcStart,
     }
   }
 }
@@ -139,6 +225,13 @@ impl SettingsMenu {
     let Config {
       resolution,
       handling_settings: hs,
+      rules,
+      sprint_goal,
+      ultra_limit,
+      cheese_goal,
+      pc_start,
+      finesse_trainer,
+      ..
     } = &mut *config_mut();
     match self.selected_option {
       Option::Resolution => {
@@ -164,6 +257,13 @@ impl SettingsMenu {
         };
         hs.das = hs.das.min(Duration::from_millis(330));
       }
+      Option::Arr => {
+        hs.arr = match change {
+          Direction::Left => hs.arr.saturating_sub(Duration::from_millis(5)),
+          Direction::Right => hs.arr.saturating_add(Duration::from_millis(5)),
+        };
+        hs.arr = hs.arr.min(Duration::from_millis(100));
+      }
       Option::SoftDrop => {
         hs.soft_drop = match change {
           Direction::Left => hs.soft_drop.saturating_sub(Duration::from_millis(10)),
@@ -171,6 +271,66 @@ impl SettingsMenu {
         };
         hs.soft_drop = hs.soft_drop.min(Duration::from_millis(330));
       }
+      Option::Randomizer => {
+        rules.randomizer = match change {
+          Direction::Left => rules.randomizer.prev(),
+          Direction::Right => rules.randomizer.next(),
+        };
+      }
+      Option::Rotation => {
+        rules.rotation_system = match change {
+          Direction::Left => rules.rotation_system.prev(),
+          Direction::Right => rules.rotation_system.next(),
+        };
+      }
+      Option::Spins => {
+        rules.spin_detection = match change {
+          Direction::Left => rules.spin_detection.prev(),
+          Direction::Right => rules.spin_detection.next(),
+        };
+      }
+      Option::Messiness => {
+        rules.garbage_messiness = match change {
+          Direction::Left => rules.garbage_messiness.saturating_sub(10),
+          Direction::Right => rules.garbage_messiness.saturating_add(10),
+        };
+        rules.garbage_messiness = rules.garbage_messiness.min(100);
+      }
+      Option::Board => {
+        let len = BOARD_SIZES.len();
+        let index = BOARD_SIZES.iter().position(|&size| size == rules.board);
+        let index = match (change, index) {
+          (Direction::Left, Some(index)) => (index + len - 1) % len,
+          (Direction::Right, Some(index)) => (index + 1) % len,
+          (_, None) => 0,
+        };
+        rules.board = BOARD_SIZES[index];
+      }
+      Option::Sprint => {
+        *sprint_goal = match change {
+          Direction::Left => sprint_goal.prev(),
+          Direction::Right => sprint_goal.next(),
+        };
+      }
+      Option::Ultra => {
+        *ultra_limit = match change {
+          Direction::Left => ultra_limit.prev(),
+          Direction::Right => ultra_limit.next(),
+        };
+      }
+      Option::Cheese => {
+        *cheese_goal = match change {
+          Direction::Left => cheese_goal.prev(),
+          Direction::Right => cheese_goal.next(),
+        };
+      }
+      Option::PcStart => {
+        *pc_start = match change {
+          Direction::Left => pc_start.prev(),
+          Direction::Right => pc_start.next(),
+        };
+      }
+      Option::FinesseTrainer => *finesse_trainer = !*finesse_trainer,
     }
   }
 
@@ -178,25 +338,65 @@ impl SettingsMenu {
     let Config {
       resolution,
       handling_settings: hs,
-    } = &mut *config_mut();
+      rules,
+      sprint_goal,
+      ultra_limit,
+      cheese_goal,
+      pc_start,
+      finesse_trainer,
+      ..
+    } = &*config();
     let (width, height) = resolution.size();
-    let font_size = height as f32 / 12.0;
+    let font_size = height as f32 / 22.0;
     let font_size_big = height as f32 / 4.0;
 
     rld.clear_background(Color::LIGHTGRAY);
     rld.draw_text(
       "SETTINGS",
       (width - rld.measure_text("SETTINGS", font_size_big as i32)) / 2,
-      height / 2 - font_size_big as i32 - font_size as i32,
+      font_size as i32,
       font_size_big as i32,
       Color::RED,
     );
 
     let resolution = ("Resolution", format!("{} x {}", width, height));
     let das = ("DAS", format!("{:0.2}", hs.das.as_secs_f32()));
+    let arr = ("ARR", format!("{:0.3}", hs.arr.as_secs_f32()));
     let soft_drop = ("Soft Drop", format!("{:0.2}", hs.soft_drop.as_secs_f32()));
+    let randomizer = ("Randomizer", rules.randomizer.name().to_string());
+    let rotation = ("Rotation", rules.rotation_system.name().to_string());
+    let spins = ("Spins", rules.spin_detection.name().to_string());
+    let messiness = ("Messiness", format!("{}%", rules.garbage_messiness));
+    let board = (
+      "Board",
+      format!("{} x {}", rules.board.width, rules.board.visible_height),
+    );
+    let sprint = ("Sprint", sprint_goal.name().to_string());
+    let ultra = ("Ultra", ultra_limit.name().to_string());
+    let cheese = ("Cheese", cheese_goal.name().to_string());
+    let pc_start = ("PC Start", pc_start.name().to_string());
+    let finesse_trainer = (
+      "Finesse Trainer",
+      if *finesse_trainer { "On" } else { "Off" }.to_string(),
+    );
 
-    let options = [resolution, das, soft_drop];
+    let options = [
+      resolution,
+      das,
+      arr,
+      soft_drop,
+      randomizer,
+      rotation,
+      spins,
+      messiness,
+      board,
+      sprint,
+      ultra,
+      cheese,
+      pc_start,
+      finesse_trainer,
+    ];
+    let options_y = font_size_big + 2.0 * font_size;
     for (i, (option, (name, value))) in zip(OPTIONS, options).enumerate() {
       let color = if self.selected_option == option {
         Color::BLUE
@@ -207,14 +407,14 @@ impl SettingsMenu {
       rld.draw_text(
         name,
         (width as f32 / 8.0) as i32,
-        (height as f32 / 2.0) as i32 + i as i32 * font_size as i32,
+        options_y as i32 + i as i32 * font_size as i32,
         font_size as i32,
         color,
       );
       rld.draw_text(
         &value,
         (width as f32 / 1.5) as i32,
-        (height as f32 / 2.0) as i32 + i as i32 * font_size as i32,
+        options_y as i32 + i as i32 * font_size as i32,
         font_size as i32,
         color,
       );
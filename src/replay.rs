@@ -0,0 +1,193 @@
+//! Recording games so they can be played back exactly.
+//!
+//! Games advance in fixed [`TICK`]s rather than by however long each frame took, so the same
+//! seed, rules, settings and inputs always lead to the same game.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{
+  HandlingSettings,
+  game::Game,
+  input::Input,
+  mode::{Mode, ModeSetup},
+  playfield::Position,
+  rules::Rules,
+};
+
+/// How far a game advances on every update.
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / 240);
+
+/// Splits frames of any length into ticks.
+#[derive(Clone, Default)]
+pub struct Ticker {
+  lag: Duration,
+  /// Presses not yet handed to a tick.
+  pressed: Input,
+}
+
+impl Ticker {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a frame lasting `dt` on which `input` was read, returning the input of every tick it
+  /// completes. Presses on a frame too short to complete a tick carry over to the next one.
+  pub fn ticks(&mut self, dt: Duration, input: Input) -> Vec<Input> {
+    self.lag += dt;
+    self.pressed = self.pressed.merge(input.pressed_only());
+
+    let mut ticks = Vec::new();
+    while self.lag >= TICK {
+      self.lag -= TICK;
+      ticks.push(input.held_only().merge(std::mem::take(&mut self.pressed)));
+    }
+    ticks
+  }
+}
+
+/// A game as its starting point and the input of every tick since.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Replay {
+  pub seed: u64,
+  pub rules: Rules,
+  pub settings: HandlingSettings,
+  pub mode: ModeSetup,
+  /// The board the game was set up with, if it did not start on an empty one.
+  #[serde(default)]
+  pub position: Option<Position>,
+  /// The inputs that differ from the one on the tick before, with the tick they start on.
+  changes: Vec<(u32, Input)>,
+  ticks: u32,
+}
+
+impl Replay {
+  /// Starts recording `game` played in `mode`, neither of which must have started yet.
+  pub fn new(game: &Game, mode: &Mode, settings: HandlingSettings) -> Self {
+    Self {
+      seed: game.seed(),
+      rules: *game.playfield().rules(),
+      settings,
+      mode: mode.setup(),
+      position: None,
+      changes: Vec::new(),
+      ticks: 0,
+    }
+  }
+
+  /// Adds the input of the next tick.
+  pub fn record(&mut self, input: Input) {
+    if self.input(self.ticks) != input {
+      self.changes.push((self.ticks, input));
+    }
+    self.ticks += 1;
+  }
+
+  /// Forgets every tick from `ticks` on, as when taking moves back.
+  pub fn truncate(&mut self, ticks: u32) {
+    self.ticks = self.ticks.min(ticks);
+    let ticks = self.ticks;
+    self.changes.retain(|&(tick, _)| tick < ticks);
+  }
+
+  /// The input on `tick`, counting from 0.
+  pub fn input(&self, tick: u32) -> Input {
+    let changes = self.changes.partition_point(|&(start, _)| start <= tick);
+    changes
+      .checked_sub(1)
+      .map_or_else(Input::default, |last| self.changes[last].1)
+  }
+
+  /// The number of ticks recorded.
+  pub fn ticks(&self) -> u32 {
+    self.ticks
+  }
+
+  pub fn duration(&self) -> Duration {
+    TICK * self.ticks
+  }
+}
+
+/// A recorded game being played again, one tick at a time.
+#[derive(Clone)]
+pub struct Playback {
+  replay: Replay,
+  game: Game,
+  mode: Mode,
+  tick: u32,
+}
+
+impl Playback {
+  pub fn new(replay: Replay) -> Self {
+    let (game, mode) = start(&replay);
+    Self {
+      replay,
+      game,
+      mode,
+      tick: 0,
+    }
+  }
+
+  /// Plays the next tick, unless the recording is over.
+  pub fn step(&mut self) {
+    if self.is_over() {
+      return;
+    }
+
+    let input = self.replay.input(self.tick);
+    self
+      .mode
+      .update(&mut self.game, TICK, input, &self.replay.settings);
+    self.tick += 1;
+  }
+
+  /// Plays up to `tick`, starting over first if it was already passed.
+  pub fn seek(&mut self, tick: u32) {
+    if tick < self.tick {
+      self.restart();
+    }
+    while self.tick < tick && !self.is_over() {
+      self.step();
+    }
+  }
+
+  /// Goes back to the start of the recording.
+  pub fn restart(&mut self) {
+    (self.game, self.mode) = start(&self.replay);
+    self.tick = 0;
+  }
+
+  pub fn is_over(&self) -> bool {
+    self.tick >= self.replay.ticks()
+  }
+
+  pub fn replay(&self) -> &Replay {
+    &self.replay
+  }
+
+  pub fn game(&self) -> &Game {
+    &self.game
+  }
+
+  pub fn mode(&self) -> &Mode {
+    &self.mode
+  }
+
+  /// The ticks played so far.
+  pub fn tick(&self) -> u32 {
+    self.tick
+  }
+
+  pub fn time(&self) -> Duration {
+    TICK * self.tick
+  }
+}
+
+/// The game and mode of `replay` before its first tick.
+fn start(replay: &Replay) -> (Game, Mode) {
+  let mut game = Game::new(replay.seed, replay.rules);
+  if let Some(position) = &replay.position {
+    game.set_up(&position.rows, &position.pieces, position.hold);
+  }
+  (game, replay.mode.mode())
+}
@@ -0,0 +1,94 @@
+//! The parts of a game that change from one ruleset to another.
+
+use serde::{Deserialize, Serialize};
+
+use crate::playfield::{
+  BoardSize, randomizer::RandomizerKind, rotation_system::RotationSystemKind,
+};
+
+/// How a game is played, as opposed to [`HandlingSettings`](crate::HandlingSettings), which
+/// describe how a player likes their pieces to move.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Rules {
+  pub randomizer: RandomizerKind,
+  pub rotation_system: RotationSystemKind,
+  pub spin_detection: SpinDetection,
+  /// How often, out of a hundred, the hole of a garbage attack moves to another column between
+  /// two of its rows.
+  pub garbage_messiness: u8,
+  pub board: BoardSize,
+  pub style: Style,
+}
+
+impl Rules {
+  /// The rules of the NES game: its randomizer, rotation without kicks and the classic style.
+  pub fn classic() -> Self {
+    Self {
+      randomizer: RandomizerKind::Nes,
+      rotation_system: RotationSystemKind::Classic,
+      style: Style::Classic,
+      ..Self::default()
+    }
+  }
+
+  /// The rules of the Tetris The Grand Master games: a history randomizer, ARS and the master
+  /// style.
+  pub fn master() -> Self {
+    Self {
+      randomizer: RandomizerKind::Tgm1,
+      rotation_system: RotationSystemKind::Ars,
+      style: Style::Master,
+      ..Self::default()
+    }
+  }
+}
+
+/// The era of the game the rest of the rules follow.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum Style {
+  #[default]
+  Guideline,
+  /// No hold, ghost piece, hard drop or 180 rotation, with NES level speeds and scoring.
+  Classic,
+  /// Levels that go up with every piece, up to 20G, with entry delays and Grand Master scoring.
+  Master,
+}
+
+/// Which pieces can spin, and how non-T spins are scored.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum SpinDetection {
+  /// Only T pieces spin, detected with the three-corner rule.
+  #[default]
+  TSpins,
+  /// S, Z, L, J and I also spin when they cannot move left, right or up, always scoring as minis.
+  AllMini,
+  /// Like [`SpinDetection::AllMini`], but scoring as a full spin.
+  AllSpin,
+}
+
+impl SpinDetection {
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::TSpins => "T-Spins",
+      Self::AllMini => "All-Mini",
+      Self::AllSpin => "All-Spin",
+    }
+  }
+
+  pub fn next(self) -> Self {
+    match self {
+      Self::TSpins => Self::AllMini,
+      Self::AllMini => Self::AllSpin,
+      Self::AllSpin => Self::TSpins,
+    }
+  }
+
+  pub fn prev(self) -> Self {
+    match self {
+      Self::TSpins => Self::AllSpin,
+      Self::AllMini => Self::TSpins,
+      Self::AllSpin => Self::AllMini,
+    }
+  }
+}
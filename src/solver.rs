@@ -0,0 +1,349 @@
+//! Searching for perfect clears.
+//!
+//! The search only considers pieces dropped straight down from above the stack, so it may miss
+//! solutions that need a tuck or a spin, but any solution it finds can be played.
+
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use crate::playfield::{Grid, tetromino::Tetromino};
+
+/// The tallest perfect clear searched for.
+pub const MAX_PC_HEIGHT: usize = 4;
+/// The widest board the search handles.
+const MAX_WIDTH: usize = 16;
+/// The most positions looked at before giving up, so that a search never stalls a frame for long.
+const MAX_NODES: u32 = 50_000;
+
+/// A piece placed on the way to a perfect clear.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Placement {
+  pub tetromino: Tetromino,
+  /// The cells of the piece as `(x, y)`, with `y = 0` being the bottom row, on the board as it
+  /// was before placing it.
+  pub cells: [(usize, usize); 4],
+  /// How many of the pieces were dealt once it was placed.
+  pub pieces_used: usize,
+  /// The piece in the hold slot once it was placed.
+  pub hold: Option<Tetromino>,
+}
+
+/// Finds a way to clear every row of `grid` by dropping `pieces` in order, with `hold` in the
+/// hold slot and the first of `pieces` being the falling one. Only perfect clears up to
+/// [`MAX_PC_HEIGHT`] rows tall are searched for, and the search gives up, finding nothing, after
+/// looking at too many positions.
+pub fn find_perfect_clear(
+  grid: &Grid,
+  pieces: &[Tetromino],
+  hold: Option<Tetromino>,
+) -> Option<Vec<Placement>> {
+  search(grid, pieces, hold, 1..=MAX_PC_HEIGHT)
+}
+
+/// Finds a perfect clear as [`find_perfect_clear`] does, but only one exactly `height` rows tall.
+pub fn find_perfect_clear_of_height(
+  grid: &Grid,
+  pieces: &[Tetromino],
+  hold: Option<Tetromino>,
+  height: usize,
+) -> Option<Vec<Placement>> {
+  search(grid, pieces, hold, height..=height.min(MAX_PC_HEIGHT))
+}
+
+fn search(
+  grid: &Grid,
+  pieces: &[Tetromino],
+  hold: Option<Tetromino>,
+  heights: RangeInclusive<usize>,
+) -> Option<Vec<Placement>> {
+  let width = grid.first().map_or(0, Vec::len);
+  if width > MAX_WIDTH {
+    return None;
+  }
+
+  let stack_height = grid
+    .iter()
+    .rposition(|row| row.iter().any(Option::is_some))
+    .map_or(0, |top| top + 1);
+  if stack_height > MAX_PC_HEIGHT {
+    return None;
+  }
+
+  let board = grid
+    .iter()
+    .take(stack_height)
+    .enumerate()
+    .map(|(y, row)| {
+      let cells = row.iter().enumerate().filter(|(_, mino)| mino.is_some());
+      cells.fold(0, |board, (x, _)| board | cell_bit(x, y))
+    })
+    .fold(0, |board, row| board | row);
+
+  let mut search = Search {
+    width,
+    pieces,
+    shapes: Tetromino::ALL.map(shapes),
+    failed: HashSet::new(),
+    path: Vec::new(),
+    nodes_left: MAX_NODES,
+  };
+  let filled = board.count_ones() as usize;
+  for height in stack_height.max(*heights.start())..=*heights.end() {
+    let empty_cells = width * height - filled;
+    let dealable = pieces.len() + hold.is_some() as usize;
+    if !empty_cells.is_multiple_of(4) || empty_cells / 4 > dealable {
+      continue;
+    }
+    if search.search(board, height, 0, hold) {
+      search.path.reverse();
+      return Some(search.path);
+    }
+  }
+  None
+}
+
+/// A piece in one orientation, as cells from its bottom left corner.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Shape {
+  cells: [(usize, usize); 4],
+  width: usize,
+  height: usize,
+}
+
+/// The distinct shapes `tetromino` takes in its four orientations.
+fn shapes(tetromino: Tetromino) -> Vec<Shape> {
+  let mut map = tetromino.initial_map();
+  let mut shapes = Vec::new();
+  for _ in 0..4 {
+    let min_x = map.iter().map(|&(x, _)| x).min().unwrap_or_default();
+    let min_y = map.iter().map(|&(_, y)| y).min().unwrap_or_default();
+    let mut cells = map.map(|(x, y)| ((x - min_x) as usize, (y - min_y) as usize));
+    cells.sort_unstable();
+    let shape = Shape {
+      cells,
+      width: cells.iter().map(|&(x, _)| x).max().unwrap_or_default() + 1,
+      height: cells.iter().map(|&(_, y)| y).max().unwrap_or_default() + 1,
+    };
+    if !shapes.contains(&shape) {
+      shapes.push(shape);
+    }
+    map = map.map(|(x, y)| (y, -x));
+  }
+  shapes
+}
+
+/// Boards are bitsets of filled cells, one row of [`MAX_WIDTH`] bits after the other.
+fn cell_bit(x: usize, y: usize) -> u64 {
+  1 << (y * MAX_WIDTH + x)
+}
+
+struct Search<'a> {
+  width: usize,
+  pieces: &'a [Tetromino],
+  shapes: [Vec<Shape>; 7],
+  /// Boards, heights, pieces dealt and held pieces known to lead nowhere.
+  failed: HashSet<(u64, usize, usize, Option<Tetromino>)>,
+  /// The placements of the solution found, from the last one back.
+  path: Vec<Placement>,
+  /// The positions that can still be looked at before giving up.
+  nodes_left: u32,
+}
+
+impl Search<'_> {
+  fn search(&mut self, board: u64, height: usize, dealt: usize, hold: Option<Tetromino>) -> bool {
+    if height == 0 {
+      return true;
+    }
+    if self.failed.contains(&(board, height, dealt, hold)) || self.nodes_left == 0 {
+      return false;
+    }
+    self.nodes_left -= 1;
+
+    let Some(&current) = self.pieces.get(dealt) else {
+      return false;
+    };
+    let mut choices = vec![(current, dealt + 1, hold)];
+    match hold {
+      Some(held) if held != current => choices.push((held, dealt + 1, Some(current))),
+      Some(_) => {}
+      None => {
+        if let Some(&next) = self.pieces.get(dealt + 1) {
+          choices.push((next, dealt + 2, Some(current)));
+        }
+      }
+    }
+
+    for (tetromino, dealt, hold) in choices {
+      for shape in self.shapes[tetromino as usize].clone() {
+        for x in 0..=self.width.saturating_sub(shape.width) {
+          let Some((cells, placed)) = self.drop(board, height, shape, x) else {
+            continue;
+          };
+          let (cleared, cleared_height) = self.clear_lines(placed, height);
+          if !self.has_fillable_gaps(cleared, cleared_height) {
+            continue;
+          }
+          if self.search(cleared, cleared_height, dealt, hold) {
+            self.path.push(Placement {
+              tetromino,
+              cells,
+              pieces_used: dealt,
+              hold,
+            });
+            return true;
+          }
+        }
+      }
+    }
+
+    self.failed.insert((board, height, dealt, hold));
+    false
+  }
+
+  /// Drops `shape` from above the board in column `x`, as long as it lands within `height` rows.
+  fn drop(
+    &self,
+    board: u64,
+    height: usize,
+    shape: Shape,
+    x: usize,
+  ) -> Option<([(usize, usize); 4], u64)> {
+    let at = |y: usize| shape.cells.map(|(cx, cy)| (x + cx, y + cy));
+    let fits = |y: usize| at(y).iter().all(|&(x, y)| board & cell_bit(x, y) == 0);
+
+    let mut y = height.checked_sub(shape.height)?;
+    if !fits(y) {
+      return None;
+    }
+    while y > 0 && fits(y - 1) {
+      y -= 1;
+    }
+
+    let cells = at(y);
+    let placed = cells
+      .iter()
+      .fold(board, |board, &(x, y)| board | cell_bit(x, y));
+    Some((cells, placed))
+  }
+
+  /// Removes the full rows of `board`, returning it with its new height.
+  fn clear_lines(&self, board: u64, height: usize) -> (u64, usize) {
+    let full_row = (1 << self.width) - 1;
+    let mut cleared = 0;
+    let mut cleared_height = 0;
+    for y in 0..height {
+      let row = (board >> (y * MAX_WIDTH)) & full_row;
+      if row != full_row {
+        cleared |= row << (cleared_height * MAX_WIDTH);
+        cleared_height += 1;
+      }
+    }
+    (cleared, cleared_height)
+  }
+
+  /// Whether every enclosed group of empty cells could still be filled with whole pieces.
+  fn has_fillable_gaps(&self, board: u64, height: usize) -> bool {
+    let row = (1 << self.width) - 1;
+    let area = (0..height).fold(0, |area, y| area | (row << (y * MAX_WIDTH)));
+    let left_column = (0..height).fold(0, |column, y| column | cell_bit(0, y));
+    let right_column = left_column << (self.width - 1);
+
+    let mut empty = !board & area;
+    while empty != 0 {
+      let mut region = empty & empty.wrapping_neg();
+      loop {
+        let grown = region
+          | ((region & !right_column) << 1)
+          | ((region & !left_column) >> 1)
+          | (region << MAX_WIDTH)
+          | (region >> MAX_WIDTH);
+        let grown = grown & empty;
+        if grown == region {
+          break;
+        }
+        region = grown;
+      }
+
+      if !region.count_ones().is_multiple_of(4) {
+        return false;
+      }
+      empty &= !region;
+    }
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::playfield::Mino;
+
+  fn empty_grid(width: usize) -> Grid {
+    vec![vec![None; width]; 2 * MAX_PC_HEIGHT]
+  }
+
+  /// Makes `placements` on `grid`, clearing full rows, and checks no piece overlaps another.
+  fn play(mut grid: Grid, placements: &[Placement]) -> Grid {
+    let width = grid[0].len();
+    for placement in placements {
+      for &(x, y) in &placement.cells {
+        assert!(grid[y][x].is_none(), "pieces should not overlap");
+        grid[y][x] = Some(Mino::Piece(placement.tetromino));
+      }
+      grid.retain(|row| row.iter().any(Option::is_none));
+      grid.resize(2 * MAX_PC_HEIGHT, vec![None; width]);
+    }
+    grid
+  }
+
+  #[test]
+  fn five_os_clear_two_rows() {
+    let grid = empty_grid(10);
+    let solution = find_perfect_clear(&grid, &[Tetromino::O; 5], None).expect("a solution");
+    assert_eq!(solution.len(), 5);
+    assert!(play(grid, &solution).iter().flatten().all(Option::is_none));
+  }
+
+  #[test]
+  fn solutions_can_use_the_hold_slot() {
+    // The T cannot help clear the well, so it has to wait in the hold slot
+    let mut grid = empty_grid(4);
+    for row in &mut grid[..4] {
+      row[..3].fill(Some(Mino::Garbage));
+    }
+    let pieces = [Tetromino::T, Tetromino::I];
+    let solution = find_perfect_clear(&grid, &pieces, None).expect("a solution");
+    assert_eq!(solution.len(), 1);
+    assert_eq!(solution[0].tetromino, Tetromino::I);
+    assert_eq!(solution[0].hold, Some(Tetromino::T));
+    assert!(play(grid, &solution).iter().flatten().all(Option::is_none));
+  }
+
+  #[test]
+  fn heights_can_be_asked_for() {
+    let grid = empty_grid(10);
+    let pieces = [Tetromino::I; 10];
+    let solution =
+      find_perfect_clear_of_height(&grid, &pieces, None, MAX_PC_HEIGHT).expect("a solution");
+    assert_eq!(solution.len(), 10);
+    assert!(play(grid, &solution).iter().flatten().all(Option::is_none));
+  }
+
+  #[test]
+  fn odd_gaps_cannot_be_cleared() {
+    let mut grid = empty_grid(10);
+    grid[0][0] = Some(Mino::Garbage);
+    assert!(find_perfect_clear(&grid, &[Tetromino::I; 10], None).is_none());
+  }
+
+  #[test]
+  fn s_pieces_alone_cannot_clear_an_empty_board() {
+    let grid = empty_grid(10);
+    assert!(find_perfect_clear(&grid, &[Tetromino::S; 11], Some(Tetromino::S)).is_none());
+  }
+
+  #[test]
+  fn too_few_pieces_cannot_clear() {
+    let grid = empty_grid(10);
+    assert!(find_perfect_clear(&grid, &[Tetromino::O; 4], None).is_none());
+  }
+}
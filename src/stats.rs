@@ -0,0 +1,114 @@
+//! How fast and how efficiently a game is being played.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::{input::Input, line_clear_message::LineClearMessage, playfield::finesse::Finesse};
+
+/// How many times a kind of clear was made.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ClearCount {
+  pub name: String,
+  pub count: u32,
+}
+
+/// Counters kept by [`crate::game::Game`] from its first update on.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct Stats {
+  time: Duration,
+  pieces: u32,
+  /// Actions pressed, holding a key down counting once.
+  keys: u32,
+  /// Lines sent, before cancelling incoming garbage.
+  attack: u32,
+  clears: Vec<ClearCount>,
+  /// Pieces placed with more keys than needed.
+  #[serde(default)]
+  finesse_faults: u32,
+}
+
+impl Stats {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Counts a frame lasting `dt` on which `input` was read.
+  pub(crate) fn add_frame(&mut self, dt: Duration, input: Input) {
+    self.time += dt;
+    self.keys += input.presses();
+  }
+
+  /// Counts a piece that locked, sending `attack` lines, placed with `finesse` and making the clear
+  /// in `message`.
+  pub(crate) fn add_lock(
+    &mut self,
+    attack: u32,
+    finesse: Option<Finesse>,
+    message: &LineClearMessage,
+  ) {
+    self.pieces += 1;
+    self.attack += attack;
+    if finesse.is_some_and(Finesse::is_fault) {
+      self.finesse_faults += 1;
+    }
+
+    let Some(name) = message.clear_name() else {
+      return;
+    };
+    match self.clears.iter_mut().find(|clear| clear.name == name) {
+      Some(clear) => clear.count += 1,
+      None => self.clears.push(ClearCount { name, count: 1 }),
+    }
+  }
+
+  pub fn time(&self) -> Duration {
+    self.time
+  }
+
+  pub fn pieces(&self) -> u32 {
+    self.pieces
+  }
+
+  pub fn keys(&self) -> u32 {
+    self.keys
+  }
+
+  pub fn attack(&self) -> u32 {
+    self.attack
+  }
+
+  pub fn finesse_faults(&self) -> u32 {
+    self.finesse_faults
+  }
+
+  /// Every kind of clear made so far, in the order they were first made.
+  pub fn clears(&self) -> &[ClearCount] {
+    &self.clears
+  }
+
+  /// Pieces per second.
+  pub fn pps(&self) -> f32 {
+    per_second(self.pieces, self.time)
+  }
+
+  /// Attack per minute.
+  pub fn apm(&self) -> f32 {
+    60.0 * per_second(self.attack, self.time)
+  }
+
+  /// Keys per piece.
+  pub fn kpp(&self) -> f32 {
+    match self.pieces {
+      0 => 0.0,
+      pieces => self.keys as f32 / pieces as f32,
+    }
+  }
+}
+
+fn per_second(count: u32, time: Duration) -> f32 {
+  if time.is_zero() {
+    0.0
+  } else {
+    count as f32 / time.as_secs_f32()
+  }
+}